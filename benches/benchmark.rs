@@ -10,21 +10,21 @@ use classif::*;
 fn mean_value(bench: &mut test::Bencher) {
     let a = get_test_values();
     let a = black_box(a);
-    bench.iter(|| { let mean = stats::mean(&a); });
+    bench.iter(|| stats::mean(&a));
 }
 
 #[bench]
 fn kurtosis_value(bench: &mut test::Bencher) {
     let a = get_test_values();
     let a = black_box(a);
-    bench.iter(|| { let mean = stats::kurtosis(&a); });
+    bench.iter(|| stats::kurtosis(&a));
 }
 
 #[bench]
 fn variance(bench: &mut test::Bencher) {
     let a = get_test_values();
     let a = black_box(a);
-    bench.iter(|| { let mean = stats::variance(&a); });
+    bench.iter(|| stats::variance(&a));
 }
 
 #[bench]
@@ -33,7 +33,7 @@ fn head_tail_raw(bench: &mut test::Bencher) {
     let mut a = black_box(a);
     bench.iter(|| {
                    a.sort_by(|i, j| i.partial_cmp(j).unwrap());
-                   let b = get_head_tail_breaks(&a);
+                   get_head_tail_breaks(&a)
                });
 }
 
@@ -41,7 +41,7 @@ fn head_tail_raw(bench: &mut test::Bencher) {
 fn head_tail(bench: &mut test::Bencher) {
     let a = get_test_values();
     let a = black_box(a);
-    bench.iter(|| { let b = BoundsInfo::new(5, &a, Classification::HeadTail).unwrap(); });
+    bench.iter(|| BoundsInfo::new(5, &a, Classification::HeadTail).unwrap());
 }
 
 #[bench]
@@ -49,7 +49,7 @@ fn jenks_breaks_sorted(bench: &mut test::Bencher) {
     let mut a = get_test_values();
     a.sort_by(|i, j| i.partial_cmp(j).unwrap());
     let a = black_box(a);
-    bench.iter(|| { let b = get_jenks_breaks(&a, 5); });
+    bench.iter(|| get_jenks_breaks(&a, 5));
 }
 
 #[bench]
@@ -58,7 +58,7 @@ fn jenks_breaks_raw(bench: &mut test::Bencher) {
     let mut a = black_box(a);
     bench.iter(|| {
                    a.sort_by(|i, j| i.partial_cmp(j).unwrap());
-                   let b = get_jenks_breaks(&a, 5);
+                   get_jenks_breaks(&a, 5)
                });
 }
 
@@ -66,7 +66,16 @@ fn jenks_breaks_raw(bench: &mut test::Bencher) {
 fn jenks_breaks(bench: &mut test::Bencher) {
     let a = get_test_values();
     let a = black_box(a);
-    bench.iter(|| { let b = BoundsInfo::new(5, &a, Classification::JenksNaturalBreaks).unwrap(); });
+    bench.iter(|| BoundsInfo::new(5, &a, Classification::JenksNaturalBreaks).unwrap());
+}
+
+#[bench]
+fn classify(bench: &mut test::Bencher) {
+    let a = get_test_values();
+    let bounds_info = BoundsInfo::new(7, &a, Classification::Quantiles).unwrap();
+    let a = black_box(a);
+    let mut classes = vec![0; a.len()];
+    bench.iter(|| bounds_info.classify_into(&a, &mut classes, 255));
 }
 
 fn get_test_values() -> [f64; 150] {
@@ -1,36 +1,355 @@
+use std::fmt;
 use std::str::FromStr;
 use num_traits::{Float, NumAssignOps};
 
-use stats::mean;
-use jenks::get_jenks_breaks;
+use error::{ClassifError, ClassifResult, MayFail, check_classif_input, check_values,
+            check_weights};
+use stats::{mean, standard_deviation, weighted_mean, sorted_quantile, QuantileMethod};
+use jenks::{get_kmeans_clusters, get_weighted_jenks_breaks};
+use method::BreaksMethod;
+use classifier::Classifier;
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// The various type of classification methods availables.
 pub enum Classification {
     EqualInterval,
     HeadTail,
+    /// Head-Tail breaks following Jiang's ht-index definition: the values are split at
+    /// their mean and the head (the values above the mean) is split again as long as it
+    /// makes up at most `head_ratio` of the values (such as 0.4), optionally stopping
+    /// when reaching `max_class` classes.
+    HeadTailRatio {
+        head_ratio: f64,
+        max_class: Option<u32>,
+    },
     TailHead,
+    /// Recursive splitting of each class at its mean
+    /// (the number of class has to be a power of two).
+    NestedMeans,
     JenksNaturalBreaks,
+    /// Optimal one-dimensional k-means clustering, the cluster centers
+    /// being available in the `centers` field of `BoundsInfo`.
+    KMeans,
     Quantiles,
     Arithmetic,
+    Geometric,
+    /// Round values (1, 2 or 5 times a power of 10) equally spaced, as computed
+    /// by the `pretty` function of R (the number of class is only indicative).
+    Pretty,
+    /// Equal intervals computed on the logarithm (in the given base) of the values,
+    /// or on the logarithm of one plus the values if `log1p` is true (allowing zeros).
+    LogEqualInterval {
+        base: f64,
+        log1p: bool,
+    },
+    /// Breaks provided by the user (see [`BoundsInfo::from_breaks`]).
+    ///
+    /// [`BoundsInfo::from_breaks`]: struct.BoundsInfo.html#method.from_breaks
+    Manual,
+    /// Breaks in the largest gaps between consecutive values.
+    MaximumBreaks {
+        placement: BreakPlacement,
+    },
+    /// Breaks at regular intervals (expressed as a fraction of the standard deviation)
+    /// from the mean, either with a break at the mean or with a class centered on it.
+    StandardDeviation {
+        width: StdDevWidth,
+        mean_centered: bool,
+    },
+    /// Breaks computed by a method provided by the user (see [`BreaksMethod`]),
+    /// recorded under its name.
+    ///
+    /// [`BreaksMethod`]: trait.BreaksMethod.html
+    Custom {
+        name: String,
+    },
 }
 
+/// Where to place a break within a gap between two consecutive values,
+/// for the `MaximumBreaks` method.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BreakPlacement {
+    /// On the value preceding the gap.
+    Lower,
+    /// Halfway between the two values.
+    Midpoint,
+    /// Just below the value following the gap (so that this value, as classes are
+    /// right-closed, belongs to the upper class).
+    Upper,
+}
+
+/// The width of the classes of the `StandardDeviation` method,
+/// as a fraction of the standard deviation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StdDevWidth {
+    One,
+    Half,
+    Quarter,
+}
+
+impl StdDevWidth {
+    /// Returns the fraction of the standard deviation used as class width.
+    pub fn factor(&self) -> f64 {
+        match *self {
+            StdDevWidth::One => 1.,
+            StdDevWidth::Half => 0.5,
+            StdDevWidth::Quarter => 0.25,
+        }
+    }
+}
+
+// Parse a positional parameter of a classification method.
+fn parse_param<P: FromStr>(param: Option<&&str>) -> Result<Option<P>, &'static str> {
+    match param {
+        Some(p) => {
+            p.parse()
+                .map(Some)
+                .map_err(|_| "Invalid classification parameter")
+        }
+        None => Ok(None),
+    }
+}
+
+fn parse_std_dev_width(param: Option<&&str>) -> Result<StdDevWidth, &'static str> {
+    match parse_param::<f64>(param)? {
+        None => Ok(StdDevWidth::One),
+        Some(1.) => Ok(StdDevWidth::One),
+        Some(0.5) => Ok(StdDevWidth::Half),
+        Some(0.25) => Ok(StdDevWidth::Quarter),
+        Some(_) => Err("Invalid standard deviation width (1, 0.5 or 0.25 expected)"),
+    }
+}
+
+fn parse_break_placement(param: Option<&&str>) -> Result<BreakPlacement, &'static str> {
+    match param.map(|p| p.to_lowercase()) {
+        None => Ok(BreakPlacement::Midpoint),
+        Some(ref p) if p == "lower" => Ok(BreakPlacement::Lower),
+        Some(ref p) if p == "midpoint" => Ok(BreakPlacement::Midpoint),
+        Some(ref p) if p == "upper" => Ok(BreakPlacement::Upper),
+        Some(_) => Err("Invalid break placement (lower, midpoint or upper expected)"),
+    }
+}
+
+/// Parse the name of a classification method, as printed by its `Display` implementation.
+///
+/// Names are case-insensitive and the `_`, `-` and space separators are optional
+/// (so that `"JenksNaturalBreaks"`, `"jenks_natural_breaks"` and `"jenks"` are all
+/// accepted). The parameters of a method can be provided, in order, between parentheses;
+/// the missing ones take their default value:
+///
+/// - `head_tail(head_ratio, max_class)` for `HeadTailRatio` (`head_tail` alone
+///   being the original Head-Tail method and `head_tail_ratio` using a 0.4 ratio),
+/// - `log_equal_interval(base, log1p)` (10 and false by default),
+/// - `maximum_breaks(lower|midpoint|upper)` (midpoint by default),
+/// - `std_dev(width, mean_centered)` with a width of 1, 0.5 or 0.25 (1 and false by default),
+/// - `custom(name)` for the methods provided by the user.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{Classification, StdDevWidth};
+/// # fn main() {
+/// let classif: Classification = "std_dev(0.5)".parse().unwrap();
+/// assert_eq!(classif,
+///            Classification::StandardDeviation { width: StdDevWidth::Half, mean_centered: false });
+/// assert_eq!(classif.to_string(), "std_dev(0.5)");
+/// assert_eq!("Natural_Breaks".parse(), Ok(Classification::JenksNaturalBreaks));
+/// # }
+/// ```
 impl FromStr for Classification {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "JenksNaturalBreaks" => Ok(Classification::JenksNaturalBreaks),
-            "Quantiles" => Ok(Classification::Quantiles),
-            "EqualInverval" => Ok(Classification::EqualInterval),
-            "HeadTail" => Ok(Classification::HeadTail),
-            "TailHead" => Ok(Classification::TailHead),
-            "Arithmetic" => Ok(Classification::Arithmetic),
+        let s = s.trim();
+        let (name, params) = match s.find('(') {
+            Some(ix) => {
+                if !s.ends_with(')') {
+                    return Err("Invalid classification parameters");
+                }
+                (&s[..ix], &s[ix + 1..s.len() - 1])
+            }
+            None => (s, ""),
+        };
+        let name = name.chars()
+            .filter(|c| !(*c == '_' || *c == '-' || c.is_whitespace()))
+            .flat_map(|c| c.to_lowercase())
+            .collect::<String>();
+        if name == "custom" {
+            // The name of a custom method is kept as is:
+            return match params.trim() {
+                "" => Err("Invalid classification parameters"),
+                custom_name => Ok(Classification::Custom { name: custom_name.to_string() }),
+            };
+        }
+        let params = if params.trim().is_empty() {
+            Vec::new()
+        } else {
+            params.split(',').map(|p| p.trim()).collect::<Vec<&str>>()
+        };
+        let max_params = match name.as_str() {
+            "headtail" | "headtailratio" | "logequalinterval" | "stddev" |
+            "standarddeviation" => 2,
+            "maximumbreaks" => 1,
+            _ => 0,
+        };
+        if params.len() > max_params {
+            return Err("Invalid classification parameters");
+        }
+        match name.as_str() {
+            "jenksnaturalbreaks" | "jenks" | "naturalbreaks" => {
+                Ok(Classification::JenksNaturalBreaks)
+            }
+            "kmeans" => Ok(Classification::KMeans),
+            "quantiles" | "quantile" => Ok(Classification::Quantiles),
+            // The "EqualInverval" misspelling is still accepted for backward compatibility:
+            "equalinterval" | "equalintervals" | "equalinverval" => {
+                Ok(Classification::EqualInterval)
+            }
+            "headtail" if params.is_empty() => Ok(Classification::HeadTail),
+            "headtail" | "headtailratio" => {
+                Ok(Classification::HeadTailRatio {
+                       head_ratio: parse_param(params.first())?.unwrap_or(0.4),
+                       max_class: parse_param(params.get(1))?,
+                   })
+            }
+            "tailhead" => Ok(Classification::TailHead),
+            "nestedmeans" => Ok(Classification::NestedMeans),
+            "arithmetic" => Ok(Classification::Arithmetic),
+            "geometric" => Ok(Classification::Geometric),
+            "pretty" => Ok(Classification::Pretty),
+            "manual" => Ok(Classification::Manual),
+            "logequalinterval" => {
+                Ok(Classification::LogEqualInterval {
+                       base: parse_param(params.first())?.unwrap_or(10.),
+                       log1p: parse_param(params.get(1))?.unwrap_or(false),
+                   })
+            }
+            "maximumbreaks" => {
+                Ok(Classification::MaximumBreaks {
+                       placement: parse_break_placement(params.first())?,
+                   })
+            }
+            "stddev" | "standarddeviation" => {
+                Ok(Classification::StandardDeviation {
+                       width: parse_std_dev_width(params.first())?,
+                       mean_centered: parse_param(params.get(1))?.unwrap_or(false),
+                   })
+            }
             _ => Err("Invalid classification name"),
         }
     }
 }
 
+/// Print the name of the classification method, followed by its parameters
+/// (in the syntax accepted by its `FromStr` implementation).
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Classification::EqualInterval => write!(f, "equal_interval"),
+            Classification::HeadTail => write!(f, "head_tail"),
+            Classification::HeadTailRatio { head_ratio, max_class } => {
+                match max_class {
+                    Some(max_class) => write!(f, "head_tail({}, {})", head_ratio, max_class),
+                    None => write!(f, "head_tail({})", head_ratio),
+                }
+            }
+            Classification::TailHead => write!(f, "tail_head"),
+            Classification::NestedMeans => write!(f, "nested_means"),
+            Classification::JenksNaturalBreaks => write!(f, "jenks_natural_breaks"),
+            Classification::KMeans => write!(f, "kmeans"),
+            Classification::Quantiles => write!(f, "quantiles"),
+            Classification::Arithmetic => write!(f, "arithmetic"),
+            Classification::Geometric => write!(f, "geometric"),
+            Classification::Pretty => write!(f, "pretty"),
+            Classification::LogEqualInterval { base, log1p } => {
+                if log1p {
+                    write!(f, "log_equal_interval({}, true)", base)
+                } else {
+                    write!(f, "log_equal_interval({})", base)
+                }
+            }
+            Classification::Manual => write!(f, "manual"),
+            Classification::MaximumBreaks { placement } => {
+                let placement = match placement {
+                    BreakPlacement::Lower => "lower",
+                    BreakPlacement::Midpoint => "midpoint",
+                    BreakPlacement::Upper => "upper",
+                };
+                write!(f, "maximum_breaks({})", placement)
+            }
+            Classification::StandardDeviation { width, mean_centered } => {
+                if mean_centered {
+                    write!(f, "std_dev({}, true)", width.factor())
+                } else {
+                    write!(f, "std_dev({})", width.factor())
+                }
+            }
+            Classification::Custom { ref name } => write!(f, "custom({})", name),
+        }
+    }
+}
+
+/// The policy to apply to the missing values (NaN or infinite values) of the input.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MissingValues {
+    /// Fail with a `ClassifError::NonFiniteValue` error.
+    #[default]
+    Error,
+    /// Ignore the missing values: they don't belong to any class.
+    Drop,
+    /// Ignore the missing values when computing the bounds but put them in
+    /// a dedicated "no data" class, following the regular ones.
+    NoDataClass,
+}
+
+/// Which end of the classes intervals is closed, ie. the class of a value
+/// equal to an interior break (following the `right` argument of R's `cut`).
+///
+/// In both cases, the first and the last classes are closed on both sides
+/// so that the minimum and the maximum belong to a class.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IntervalClosure {
+    /// Right-closed intervals `(a, b]`: a value equal to a break belongs to the lower class.
+    #[default]
+    Right,
+    /// Left-closed intervals `[a, b)`: a value equal to a break belongs to the upper class.
+    Left,
+}
+
+impl MissingValues {
+    /// Apply the policy on `values`, returning the finite values
+    /// and the number of missing values put aside.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{stats, MissingValues};
+    /// # fn main() {
+    /// let values = [1., 2., std::f64::NAN, 3.];
+    /// let (finite_values, nb_missing) = MissingValues::Drop.filter(&values).unwrap();
+    /// assert_eq!(nb_missing, 1);
+    /// assert_eq!(stats::mean(&finite_values).unwrap(), 2.);
+    /// # }
+    /// ```
+    pub fn filter<T>(&self, values: &[T]) -> ClassifResult<(Vec<T>, usize)>
+        where T: Float
+    {
+        let finite_values = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .cloned()
+            .collect::<Vec<T>>();
+        let nb_missing = values.len() - finite_values.len();
+        if nb_missing > 0 && *self == MissingValues::Error {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        Ok((finite_values, nb_missing))
+    }
+}
+
 /// A struct containing the bounds computed at its creation and some basic
 /// statistical informations : minimum, maximum and mean value.
 ///
@@ -59,6 +378,11 @@ impl FromStr for Classification {
 /// let ix = bounds_info.get_class_index(4.4).unwrap();
 /// ```
 /// [`get_class_index`]: struct.BoundsInfo.html#method.get_class_index
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde",
+           serde(try_from = "BoundsInfoData<T>",
+                 bound(deserialize = "T: Float + NumAssignOps + ::serde::Deserialize<'de>")))]
 pub struct BoundsInfo<T> {
     pub type_classif: Classification,
     pub nb_class: u32,
@@ -66,61 +390,454 @@ pub struct BoundsInfo<T> {
     pub min: T,
     pub max: T,
     pub mean: T,
+    /// The ht-index of the values, when computed by the classification
+    /// method (ie. the `HeadTailRatio` method).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ht_index: Option<u32>,
+    /// The center of each class, when the classification method
+    /// computes them (ie. the `KMeans` method).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub centers: Option<Vec<T>>,
+    /// The policy applied to the missing values of the input.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub missing_values: MissingValues,
+    /// The number of missing values (NaN or infinite values) which were
+    /// left aside when computing the bounds.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nb_missing: usize,
+    /// The closure of the classes intervals, used by the class lookup methods
+    /// (right-closed intervals by default).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub closure: IntervalClosure,
+}
+
+// The deserialized fields of a `BoundsInfo`, whose consistency is checked
+// before building it (so that the class lookup methods can't panic).
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: ::serde::Deserialize<'de>"))]
+struct BoundsInfoData<T> {
+    type_classif: Classification,
+    nb_class: u32,
+    bounds: Vec<T>,
+    min: T,
+    max: T,
+    mean: T,
+    #[serde(default)]
+    ht_index: Option<u32>,
+    #[serde(default)]
+    centers: Option<Vec<T>>,
+    #[serde(default)]
+    missing_values: MissingValues,
+    #[serde(default)]
+    nb_missing: usize,
+    #[serde(default)]
+    closure: IntervalClosure,
+}
+
+#[cfg(feature = "serde")]
+impl<T> ::std::convert::TryFrom<BoundsInfoData<T>> for BoundsInfo<T>
+    where T: Float + NumAssignOps
+{
+    type Error = ClassifError;
+
+    fn try_from(data: BoundsInfoData<T>) -> ClassifResult<Self> {
+        if data.bounds.len() < 2 {
+            return Err(ClassifError::InvalidParameter("at least two bounds are required"));
+        }
+        if data.bounds.iter().any(|b| !b.is_finite()) {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        if data.bounds.windows(2).any(|w| w[0] > w[1]) {
+            return Err(ClassifError::UnsortedValues);
+        }
+        if data.nb_class as usize != data.bounds.len() - 1 {
+            return Err(ClassifError::InvalidClassNumber(data.nb_class));
+        }
+        Ok(BoundsInfo {
+               type_classif: data.type_classif,
+               nb_class: data.nb_class,
+               bounds: data.bounds,
+               min: data.min,
+               max: data.max,
+               mean: data.mean,
+               ht_index: data.ht_index,
+               centers: data.centers,
+               missing_values: data.missing_values,
+               nb_missing: data.nb_missing,
+               closure: data.closure,
+           })
+    }
 }
 
 impl<T> BoundsInfo<T>
     where T: Float + NumAssignOps
 {
+    /// Compute the bounds of `nb_class` classes on `values` (which don't need to be sorted)
+    /// according to the `type_classif` method.
+    ///
+    /// Returns an error if there is less than two values, if the number of class is
+    /// invalid (it is ignored by the Head-Tail, Tail-Head and Standard Deviation methods
+    /// and only indicative, but between 1 and the number of values, for the Pretty method),
+    /// if the values contain NaN or infinite values or if all the values are equal.
+    ///
+    /// This is a shortcut for `Classifier::new(type_classif).classes(nb_class).build(values)`,
+    /// see [`Classifier`] for the other available options.
+    ///
+    /// [`Classifier`]: struct.Classifier.html
     pub fn new(nb_class: u32,
                values: &[T],
                type_classif: Classification)
-               -> Result<Self, &'static str> {
-        let nb_elem = values.len();
-        if nb_elem < 2 {
-            return Err("Too small number of values!".into());
-        } else if !(type_classif == Classification::HeadTail ||
-                    type_classif == Classification::TailHead) &&
-                  (nb_class < 2 || nb_class > nb_elem as u32) {
-            return Err("Invalid number of class");
-        }
-        let mut v = values.to_vec();
+               -> ClassifResult<Self> {
+        Classifier::new(type_classif)
+            .classes(nb_class)
+            .build(values)
+    }
+
+    /// Same as [`new`] but allows to choose how to handle the missing values
+    /// (NaN or infinite values) of the input.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification, MissingValues};
+    /// # fn main() {
+    /// let values = [1., 2., std::f64::NAN, 3., 4., 5.];
+    /// let bounds_info = BoundsInfo::with_missing_values(
+    ///     2, &values, Classification::EqualInterval, MissingValues::NoDataClass).unwrap();
+    /// assert_eq!(bounds_info.nb_missing, 1);
+    /// // Missing values belong to the "no data" class, following the regular ones:
+    /// assert_eq!(bounds_info.get_class_index(std::f64::NAN), Some(2));
+    /// # }
+    /// ```
+    /// [`new`]: struct.BoundsInfo.html#method.new
+    pub fn with_missing_values(nb_class: u32,
+                               values: &[T],
+                               type_classif: Classification,
+                               missing_values: MissingValues)
+                               -> ClassifResult<Self> {
+        Classifier::new(type_classif)
+            .classes(nb_class)
+            .missing_values(missing_values)
+            .build(values)
+    }
+
+    /// Same as [`new`] but on values which are already sorted in ascending order,
+    /// avoiding to copy and to sort them.
+    ///
+    /// Returns an error if the values are not sorted (which is checked in linear time).
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification};
+    /// # fn main() {
+    /// let sorted_values = [1., 1.3, 2.1, 2.4, 3., 4., 4.3, 5., 5.3, 6.];
+    /// let bounds_info = BoundsInfo::from_sorted(2, &sorted_values, Classification::EqualInterval)
+    ///     .unwrap();
+    /// assert_eq!(bounds_info.bounds, vec![1., 3.5, 6.]);
+    /// assert!(BoundsInfo::from_sorted(2, &[3., 1., 2.], Classification::EqualInterval).is_err());
+    /// # }
+    /// ```
+    /// [`new`]: struct.BoundsInfo.html#method.new
+    pub fn from_sorted(nb_class: u32,
+                       sorted_values: &[T],
+                       type_classif: Classification)
+                       -> ClassifResult<Self> {
+        Classifier::new(type_classif)
+            .classes(nb_class)
+            .build_sorted(sorted_values)
+    }
+
+    // Compute the bounds, the options of the classification having already been validated.
+    pub(crate) fn compute(nb_class: u32,
+                          values: &[T],
+                          type_classif: Classification,
+                          missing_values: MissingValues)
+                          -> ClassifResult<Self> {
+        let (mut v, nb_missing) = missing_values.filter(values)?;
         v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        BoundsInfo::compute_sorted(nb_class, &v, type_classif, missing_values, nb_missing)
+    }
+
+    // Same as `compute` on sorted finite values.
+    pub(crate) fn compute_sorted(nb_class: u32,
+                                 v: &[T],
+                                 type_classif: Classification,
+                                 missing_values: MissingValues,
+                                 nb_missing: usize)
+                                 -> ClassifResult<Self> {
+        let checked_nb_class = match type_classif {
+            Classification::HeadTail |
+            Classification::HeadTailRatio { .. } |
+            Classification::TailHead |
+            Classification::Pretty |
+            Classification::StandardDeviation { .. } => None,
+            _ => Some(nb_class),
+        };
+        check_classif_input(v, checked_nb_class)?;
+        let mut centers = None;
+        let mut ht_index = None;
         let breaks = match type_classif {
-            Classification::JenksNaturalBreaks => get_jenks_breaks(&v, nb_class),
-            Classification::Quantiles => get_quantiles(&v, nb_class),
-            Classification::EqualInterval => get_equal_interval(&v, nb_class),
-            Classification::HeadTail => get_head_tail_breaks(&v),
-            Classification::TailHead => get_tail_head_breaks(&v),
-            Classification::Arithmetic => get_arithmetic_breaks(&v, nb_class),
+            Classification::KMeans => {
+                let clusters = get_kmeans_clusters(v, nb_class)?;
+                centers = Some(clusters.centers);
+                clusters.breaks
+            }
+            Classification::HeadTailRatio { head_ratio, .. } => {
+                ht_index = Some(get_ht_index(v, head_ratio)?);
+                type_classif.breaks(v, nb_class)?
+            }
+            _ => type_classif.breaks(v, nb_class)?,
         };
         Ok(BoundsInfo {
-               type_classif: type_classif,
+               type_classif,
+               nb_class: (breaks.len() - 1) as u32,
+               bounds: breaks,
+               min: v[0],
+               max: v[v.len() - 1],
+               mean: mean(v)?,
+               ht_index,
+               centers,
+               missing_values,
+               nb_missing,
+               closure: IntervalClosure::default(),
+           })
+    }
+
+    /// Compute the bounds of `nb_class` classes on `values` (which don't need to be sorted),
+    /// each value having a weight (such as the population or the area of a feature)
+    /// given in the parallel `weights` slice.
+    ///
+    /// Weights are taken into account by the `Quantiles` method (each class holding the
+    /// same total weight), by the `JenksNaturalBreaks` method (minimizing the weighted sum
+    /// of squared deviations) and by the `mean` field. Methods only depending on the range
+    /// of the values (`EqualInterval`, `Arithmetic`, `Geometric`, `LogEqualInterval`,
+    /// `Pretty` and `MaximumBreaks`) are computed as usual, other methods are rejected.
+    /// The missing values (NaN or infinite values) of `values` are handled according to
+    /// `missing_values`, their weights being ignored.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification, MissingValues};
+    /// # fn main() {
+    /// let values = [1., 2., 3., 4.];
+    /// let weights = [1., 1., 1., 3.];
+    /// let bounds_info = BoundsInfo::with_weights(2,
+    ///                                            &values,
+    ///                                            &weights,
+    ///                                            Classification::Quantiles,
+    ///                                            MissingValues::Error)
+    ///     .unwrap();
+    /// assert_eq!(bounds_info.bounds, vec![1., 3., 4.]);
+    /// assert_eq!(bounds_info.mean, 3.);
+    /// # }
+    /// ```
+    pub fn with_weights(nb_class: u32,
+                        values: &[T],
+                        weights: &[T],
+                        type_classif: Classification,
+                        missing_values: MissingValues)
+                        -> ClassifResult<Self> {
+        if weights.len() != values.len() {
+            return Err(ClassifError::LengthMismatch(values.len(), weights.len()));
+        }
+        let nb_missing = missing_values.filter(values)?.1;
+        let (finite_values, finite_weights): (Vec<T>, Vec<T>) = values
+            .iter()
+            .cloned()
+            .zip(weights.iter().cloned())
+            .filter(|&(v, _)| v.is_finite())
+            .unzip();
+        check_classif_input(&finite_values, Some(nb_class))?;
+        check_weights(&finite_values, &finite_weights)?;
+        let weighted_mean = weighted_mean(&finite_values, &finite_weights)?;
+        match type_classif {
+            Classification::Quantiles |
+            Classification::JenksNaturalBreaks => (),
+            Classification::EqualInterval |
+            Classification::Arithmetic |
+            Classification::Geometric |
+            Classification::LogEqualInterval { .. } |
+            Classification::Pretty |
+            Classification::MaximumBreaks { .. } => {
+                let mut bounds_info =
+                    BoundsInfo::with_missing_values(nb_class, values, type_classif, missing_values)?;
+                bounds_info.mean = weighted_mean;
+                return Ok(bounds_info);
+            }
+            _ => {
+                return Err(ClassifError::InvalidParameter("this classification method doesn't \
+                                                           support weights"))
+            }
+        }
+        let mut pairs = finite_values
+            .into_iter()
+            .zip(finite_weights)
+            .collect::<Vec<(T, T)>>();
+        pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let (v, w): (Vec<T>, Vec<T>) = pairs.into_iter().unzip();
+        let breaks = if type_classif == Classification::Quantiles {
+            get_weighted_quantiles(&v, &w, nb_class)
+        } else {
+            get_weighted_jenks_breaks(&v, &w, nb_class)
+        }?;
+        Ok(BoundsInfo {
+               type_classif,
                nb_class: (breaks.len() - 1) as u32,
                bounds: breaks,
                min: v[0],
                max: v[v.len() - 1],
-               mean: mean(&v),
+               mean: weighted_mean,
+               ht_index: None,
+               centers: None,
+               missing_values,
+               nb_missing,
+               closure: IntervalClosure::default(),
+           })
+    }
+
+    /// Create a `BoundsInfo` from breaks provided by the user (such as official thresholds),
+    /// in order to use the same class lookup and statistics as on computed breaks.
+    ///
+    /// The breaks have to be finite and strictly increasing. If `extend_to_range` is true,
+    /// the first and the last breaks are extended to the minimum and the maximum of
+    /// `values` when they don't already cover them. The missing values (NaN or infinite
+    /// values) of `values` are handled according to `missing_values`.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification, MissingValues};
+    /// # fn main() {
+    /// let values = [1.0, 1.3, 2.4, 5.0, 2.1, 5.3, 4.0, 3.0, 1.3, 4.3, 6.0, 2.1];
+    /// let bounds_info = BoundsInfo::from_breaks(&[2., 4., 5.], &values, true, MissingValues::Error)
+    ///     .unwrap();
+    /// assert_eq!(bounds_info.type_classif, Classification::Manual);
+    /// assert_eq!(bounds_info.bounds, vec![1., 4., 6.]);
+    /// assert_eq!(bounds_info.get_class_index(4.3), Some(1));
+    /// # }
+    /// ```
+    pub fn from_breaks(breaks: &[T],
+                       values: &[T],
+                       extend_to_range: bool,
+                       missing_values: MissingValues)
+                       -> ClassifResult<Self> {
+        if breaks.len() < 2 {
+            return Err(ClassifError::InvalidParameter("at least two breaks are required"));
+        }
+        if breaks.iter().any(|b| !b.is_finite()) {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        if breaks.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(ClassifError::InvalidParameter("breaks have to be strictly increasing"));
+        }
+        let (values, nb_missing) = missing_values.filter(values)?;
+        check_values(&values, 1, None)?;
+        let min = values.iter().fold(T::infinity(), |m, v| m.min(*v));
+        let max = values.iter().fold(T::neg_infinity(), |m, v| m.max(*v));
+        let mut bounds = breaks.to_vec();
+        if extend_to_range {
+            let last = bounds.len() - 1;
+            bounds[0] = bounds[0].min(min);
+            bounds[last] = bounds[last].max(max);
+        }
+        Ok(BoundsInfo {
+               type_classif: Classification::Manual,
+               nb_class: (bounds.len() - 1) as u32,
+               bounds,
+               min,
+               max,
+               mean: mean(&values)?,
+               ht_index: None,
+               centers: None,
+               missing_values,
+               nb_missing,
+               closure: IntervalClosure::default(),
            })
     }
 
     /// Returns the index of the class to which the `value` belongs, wrapped
     /// in an Option. Returns None if the value is outside the serie range.
+    ///
+    /// A value equal to an interior break belongs to the lower or to the upper class
+    /// depending on the `closure` of the intervals:
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, IntervalClosure, MissingValues};
+    /// # fn main() {
+    /// let values = [1., 2., 3., 4., 5.];
+    /// let mut bounds_info =
+    ///     BoundsInfo::from_breaks(&[1., 3., 5.], &values, false, MissingValues::Error).unwrap();
+    /// assert_eq!(bounds_info.get_class_index(3.), Some(0));
+    /// bounds_info.closure = IntervalClosure::Left;
+    /// assert_eq!(bounds_info.get_class_index(3.), Some(1));
+    /// assert_eq!(bounds_info.get_class_index(5.), Some(1));
+    /// # }
+    /// ```
+    ///
+    /// Missing values (NaN or infinite values) belong to no class, unless the bounds were
+    /// computed with the `MissingValues::NoDataClass` policy: the index of the "no data"
+    /// class (i.e. `nb_class`) is then returned.
     pub fn get_class_index(&self, value: T) -> Option<u32> {
-        for i in 0..self.bounds.len() - 1 {
-            if value <= self.bounds[i + 1usize] && value >= self.bounds[i] {
-                return Some(i as u32);
-            }
+        if !value.is_finite() {
+            return match self.missing_values {
+                MissingValues::NoDataClass => Some(self.nb_class),
+                _ => None,
+            };
         }
-        None
+        let last = self.bounds.len() - 1;
+        if value < self.bounds[0] || value > self.bounds[last] {
+            return None;
+        }
+        let interior_bounds = &self.bounds[1..last];
+        let ix = match self.closure {
+            IntervalClosure::Right => interior_bounds.partition_point(|b| *b < value),
+            IntervalClosure::Left => interior_bounds.partition_point(|b| *b <= value),
+        };
+        Some(ix as u32)
+    }
+
+    /// Returns the index of the class of each value of `values` (see [`get_class_index`]),
+    /// using a binary search over the bounds.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification};
+    /// # fn main() {
+    /// let values = [1., 2., 3., 4., 5., 6., 7., 8.];
+    /// let bounds_info = BoundsInfo::new(2, &values, Classification::EqualInterval).unwrap();
+    /// assert_eq!(bounds_info.classify(&[1., 4.5, 6., 9.]), vec![Some(0), Some(0), Some(1), None]);
+    /// # }
+    /// ```
+    /// [`get_class_index`]: struct.BoundsInfo.html#method.get_class_index
+    pub fn classify(&self, values: &[T]) -> Vec<Option<u32>> {
+        values.iter().map(|v| self.get_class_index(*v)).collect()
+    }
+
+    /// Same as [`classify`] but writes the class indexes in `classes` (which must have
+    /// the same length as `values`), using `out_of_range` as label for the values which
+    /// belong to no class.
+    ///
+    /// [`classify`]: struct.BoundsInfo.html#method.classify
+    pub fn classify_into(&self,
+                         values: &[T],
+                         classes: &mut [u32],
+                         out_of_range: u32)
+                         -> ClassifResult<()> {
+        if values.len() != classes.len() {
+            return Err(ClassifError::LengthMismatch(values.len(), classes.len()));
+        }
+        for (class, v) in classes.iter_mut().zip(values) {
+            *class = self.get_class_index(*v).unwrap_or(out_of_range);
+        }
+        Ok(())
     }
 }
 
 /// Compute the equal interval breaks on a list of sorted values.
-pub fn get_equal_interval<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
+pub fn get_equal_interval<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
     where T: Float + NumAssignOps
 {
-    // values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    // let nb_elem = values.len();
+    check_classif_input(sorted_values, Some(nb_class))?;
     let min = sorted_values.first().unwrap();
     let max = sorted_values.last().unwrap();
     let interval = (*max - *min) / T::from(nb_class).unwrap();
@@ -134,31 +851,88 @@ pub fn get_equal_interval<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
         let last = breaks.last_mut().unwrap();
         *last = *max;
     }
-    breaks
+    Ok(breaks)
 }
 
 /// Compute the quantiles breaks on a list of sorted values.
-pub fn get_quantiles<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
+///
+/// The breaks are computed using the `QuantileMethod::Legacy` rule, see
+/// [`get_quantiles_with_method`] to use one of the rules available in R or NumPy.
+///
+/// [`get_quantiles_with_method`]: fn.get_quantiles_with_method.html
+pub fn get_quantiles<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
+    where T: Float
+{
+    get_quantiles_with_method(sorted_values, nb_class, QuantileMethod::Legacy)
+}
+
+/// Compute the quantiles breaks on a list of sorted values,
+/// using the given quantile `method`.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{get_quantiles_with_method, stats::QuantileMethod};
+/// # fn main() {
+/// let values = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+/// let breaks = get_quantiles_with_method(&values, 4, QuantileMethod::Type7).unwrap();
+/// assert_eq!(breaks, vec![1., 3.25, 5.5, 7.75, 10.]);
+/// # }
+/// ```
+pub fn get_quantiles_with_method<T>(sorted_values: &[T],
+                                    nb_class: u32,
+                                    method: QuantileMethod)
+                                    -> ClassifResult<Vec<T>>
     where T: Float
 {
+    check_classif_input(sorted_values, Some(nb_class))?;
     let nb_elem: usize = sorted_values.len();
     let mut breaks = Vec::new();
     breaks.push(sorted_values[0]);
-    let step = nb_elem as f64 / nb_class as f64;
     for i in 1..nb_class {
-        let qidx = (i as f64 * step + 0.49).floor() as usize;
-        breaks.push(sorted_values[qidx - 1]);
+        breaks.push(sorted_quantile(sorted_values, i as f64 / nb_class as f64, method));
     }
     breaks.push(sorted_values[nb_elem - 1]);
-    breaks
+    Ok(breaks)
+}
+
+/// Compute the weighted quantiles breaks on a list of sorted values, each class
+/// holding (as much as possible) the same total weight.
+///
+/// `weights` is a list of positive (or zero) weights, in the same order as `sorted_values`.
+pub fn get_weighted_quantiles<T>(sorted_values: &[T],
+                                 weights: &[T],
+                                 nb_class: u32)
+                                 -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    check_weights(sorted_values, weights)?;
+    let nb_elem: usize = sorted_values.len();
+    let total = weights.iter().fold(T::zero(), |s, w| s + *w);
+    let mut breaks = Vec::new();
+    breaks.push(sorted_values[0]);
+    let mut cumulative = T::zero();
+    let mut ix = 0;
+    for i in 1..nb_class {
+        let target = total * T::from(i).unwrap() / T::from(nb_class).unwrap();
+        // The break is the first value whose cumulative weight reaches the target:
+        while ix < nb_elem - 1 && cumulative + weights[ix] < target {
+            cumulative += weights[ix];
+            ix += 1;
+        }
+        breaks.push(sorted_values[ix]);
+    }
+    breaks.push(sorted_values[nb_elem - 1]);
+    Ok(breaks)
 }
 
 /// Compute the "Head-Tail" breaks on a list of sorted values
 /// (to be used on heavily right skewed distributions).
-pub fn get_head_tail_breaks<T>(sorted_values: &[T]) -> Vec<T>
+pub fn get_head_tail_breaks<T>(sorted_values: &[T]) -> ClassifResult<Vec<T>>
     where T: Float + NumAssignOps
 {
-    let mut _mean = mean(&sorted_values);
+    check_classif_input(sorted_values, None)?;
+    let mut _mean = mean(sorted_values)?;
     let mut breaks = Vec::new();
     let mut t;
     breaks.push(sorted_values[0]);
@@ -168,22 +942,74 @@ pub fn get_head_tail_breaks<T>(sorted_values: &[T]) -> Vec<T>
             .filter(|&v| *v > _mean)
             .cloned()
             .collect::<Vec<T>>();
-        _mean = mean(&t);
+        // The last mean was the repeated extreme value:
+        if t.is_empty() {
+            break;
+        }
+        _mean = mean(&t)?;
         breaks.push(_mean);
         if t.len() < 2 {
             break;
         }
     }
-    breaks
+    Ok(breaks)
+}
+
+/// Compute the "Head-Tail" breaks on a list of sorted values following Jiang's
+/// definition: the values are split at their mean, then the head (the values above
+/// the mean) is split again at its own mean, and so on as long as the head makes up
+/// at most `head_ratio` of the values being split (such as 0.4, ie. 40%).
+///
+/// If `max_class` is provided, the splitting stops when reaching this number of class.
+pub fn get_head_tail_ratio_breaks<T>(sorted_values: &[T],
+                                     head_ratio: f64,
+                                     max_class: Option<u32>)
+                                     -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, None)?;
+    if !(head_ratio > 0. && head_ratio < 1.) {
+        return Err(ClassifError::InvalidParameter("head ratio"));
+    }
+    let max_class = match max_class {
+        Some(nb_class) if nb_class < 2 => return Err(ClassifError::InvalidClassNumber(nb_class)),
+        Some(nb_class) => nb_class as usize,
+        None => sorted_values.len(),
+    };
+    let mut breaks = vec![sorted_values[0]];
+    // The values being split (the head of the previous split):
+    let mut head = sorted_values;
+    while breaks.len() < max_class {
+        let head_mean = mean(head)?;
+        let split = head.partition_point(|v| *v <= head_mean);
+        let nb_head = head.len() - split;
+        if nb_head == 0 || nb_head as f64 / head.len() as f64 > head_ratio {
+            break;
+        }
+        breaks.push(head_mean);
+        head = &head[split..];
+    }
+    breaks.push(sorted_values[sorted_values.len() - 1]);
+    Ok(breaks)
+}
+
+/// Compute the ht-index of a list of sorted values, ie. the number of class
+/// given by the Head-Tail breaks (without limit on the number of class),
+/// which measures the hierarchy (or the "scaling pattern") of the values.
+pub fn get_ht_index<T>(sorted_values: &[T], head_ratio: f64) -> ClassifResult<u32>
+    where T: Float + NumAssignOps
+{
+    get_head_tail_ratio_breaks(sorted_values, head_ratio, None).map(|b| (b.len() - 1) as u32)
 }
 
 /// Compute the "Tail-Head" breaks on a list of sorted values
 /// (its actually just the inverse of the Head-Tail method,
 /// to be used on heavily left skewed distributions).
-pub fn get_tail_head_breaks<T>(sorted_values: &[T]) -> Vec<T>
+pub fn get_tail_head_breaks<T>(sorted_values: &[T]) -> ClassifResult<Vec<T>>
     where T: Float + NumAssignOps
 {
-    let mut _mean = mean(&sorted_values);
+    check_classif_input(sorted_values, None)?;
+    let mut _mean = mean(sorted_values)?;
     let mut breaks = Vec::new();
     let mut t;
     breaks.push(*sorted_values.last().unwrap());
@@ -193,20 +1019,63 @@ pub fn get_tail_head_breaks<T>(sorted_values: &[T]) -> Vec<T>
             .filter(|&v| *v < _mean)
             .cloned()
             .collect::<Vec<T>>();
-        _mean = mean(&t);
+        // The last mean was the repeated extreme value:
+        if t.is_empty() {
+            break;
+        }
+        _mean = mean(&t)?;
         breaks.push(_mean);
         if t.len() < 2 {
             break;
         }
     }
     breaks.reverse();
-    breaks
+    Ok(breaks)
+}
+
+/// Compute the "nested means" breaks on a list of sorted values: the values are split
+/// at their mean, then each of the two classes is split at its own mean, and so on
+/// until reaching the requested `depth` (giving `2^depth` classes).
+pub fn get_nested_means_breaks<T>(sorted_values: &[T], depth: u32) -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    let nb_class = match 1u32.checked_shl(depth) {
+        Some(nb_class) => nb_class,
+        None => return Err(ClassifError::InvalidParameter("depth")),
+    };
+    check_classif_input(sorted_values, Some(nb_class))?;
+    let mut breaks = vec![sorted_values[0], sorted_values[sorted_values.len() - 1]];
+    // The range of indexes of the values of each class:
+    let mut ranges = vec![(0, sorted_values.len())];
+    for _ in 0..depth {
+        let mut next_breaks = Vec::with_capacity(breaks.len() * 2 - 1);
+        let mut next_ranges = Vec::with_capacity(ranges.len() * 2);
+        next_breaks.push(breaks[0]);
+        for (i, &(start, end)) in ranges.iter().enumerate() {
+            let class_values = &sorted_values[start..end];
+            // An empty class can't be split anymore:
+            let class_mean = if class_values.is_empty() {
+                breaks[i + 1]
+            } else {
+                mean(class_values)?
+            };
+            let split = start + class_values.partition_point(|v| *v <= class_mean);
+            next_breaks.push(class_mean);
+            next_breaks.push(breaks[i + 1]);
+            next_ranges.push((start, split));
+            next_ranges.push((split, end));
+        }
+        breaks = next_breaks;
+        ranges = next_ranges;
+    }
+    Ok(breaks)
 }
 
 /// Compute the "arithmetic progression" breaks on a list of sorted values.
-pub fn get_arithmetic_breaks<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
+pub fn get_arithmetic_breaks<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
     where T: Float + NumAssignOps
 {
+    check_classif_input(sorted_values, Some(nb_class))?;
     let mut denominator = T::zero();
     for i in 1..nb_class + 1 {
         denominator += T::from(i).unwrap();
@@ -220,5 +1089,144 @@ pub fn get_arithmetic_breaks<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
         let v = breaks[(i - 1) as usize];
         breaks.push(v + (T::from(i).unwrap() * interval));
     }
-    breaks
+    Ok(breaks)
+}
+
+/// Compute the "geometric progression" breaks on a list of sorted values
+/// (to be used on positive right skewed distributions): the ratio between
+/// the upper and the lower bound of each class is constant.
+pub fn get_geometric_breaks<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    if sorted_values.iter().any(|v| *v <= T::zero()) {
+        return Err(ClassifError::OnlyPositive(MayFail::GeometricBreaks));
+    }
+    let tmp_min = sorted_values[0];
+    let tmp_max = sorted_values[sorted_values.len() - 1];
+    // Computed on the logarithms, as `tmp_max / tmp_min` overflows on wide ranges:
+    let ratio = ((tmp_max.ln() - tmp_min.ln()) / T::from(nb_class).unwrap()).exp();
+    let mut breaks = Vec::with_capacity(nb_class as usize + 1);
+    let mut val = tmp_min;
+    for _ in 0..nb_class {
+        breaks.push(val);
+        val *= ratio;
+    }
+    breaks.push(tmp_max);
+    Ok(breaks)
+}
+
+/// Compute equal interval breaks on the logarithm in base `base` of a list of sorted values
+/// (or on the logarithm of one plus the values if `log1p` is true), the breaks being
+/// returned in the original units of the values.
+///
+/// Values must be strictly positive (or positive or zero if `log1p` is true).
+pub fn get_log_equal_interval<T>(sorted_values: &[T],
+                                 nb_class: u32,
+                                 base: f64,
+                                 log1p: bool)
+                                 -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    if !(base > 0. && base != 1. && base.is_finite()) {
+        return Err(ClassifError::InvalidParameter("logarithm base"));
+    }
+    let lower_limit = if log1p { T::zero() } else { T::min_positive_value() };
+    if sorted_values.iter().any(|v| *v < lower_limit) {
+        return Err(ClassifError::OnlyPositive(MayFail::LogEqualInterval));
+    }
+    let base = T::from(base).unwrap();
+    let offset = if log1p { T::one() } else { T::zero() };
+    let tmp_min = sorted_values[0];
+    let tmp_max = sorted_values[sorted_values.len() - 1];
+    let log_min = (tmp_min + offset).log(base);
+    let interval = ((tmp_max + offset).log(base) - log_min) / T::from(nb_class).unwrap();
+    let mut breaks = Vec::with_capacity(nb_class as usize + 1);
+    breaks.push(tmp_min);
+    for i in 1..nb_class {
+        breaks.push(base.powf(log_min + T::from(i).unwrap() * interval) - offset);
+    }
+    breaks.push(tmp_max);
+    Ok(breaks)
+}
+
+/// Compute the "maximum breaks" on a list of sorted values: the breaks are placed
+/// in the `nb_class - 1` largest gaps between consecutive values
+/// (on the lower value, just below the upper value or halfway, according to `placement`),
+/// so that the last break is always the maximum.
+///
+/// Returns an error if there isn't enough distinct values to make `nb_class` classes.
+pub fn get_maximum_breaks<T>(sorted_values: &[T],
+                             nb_class: u32,
+                             placement: BreakPlacement)
+                             -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    let nb_elem = sorted_values.len();
+    // Index of the value preceding each gap, from the largest gap to the smallest:
+    let mut gaps = (0..nb_elem - 1)
+        .filter(|&i| sorted_values[i + 1] > sorted_values[i])
+        .collect::<Vec<usize>>();
+    if gaps.len() < (nb_class - 1) as usize {
+        return Err(ClassifError::InvalidClassNumber(nb_class));
+    }
+    gaps.sort_by(|&a, &b| {
+        let gap_a = sorted_values[a + 1] - sorted_values[a];
+        let gap_b = sorted_values[b + 1] - sorted_values[b];
+        gap_b.partial_cmp(&gap_a).unwrap()
+    });
+    gaps.truncate((nb_class - 1) as usize);
+    gaps.sort();
+    let mut breaks = Vec::with_capacity(nb_class as usize + 1);
+    breaks.push(sorted_values[0]);
+    for i in gaps {
+        let (lower, upper) = (sorted_values[i], sorted_values[i + 1]);
+        breaks.push(match placement {
+                        BreakPlacement::Lower => lower,
+                        BreakPlacement::Midpoint => (lower + upper) / T::from(2).unwrap(),
+                        BreakPlacement::Upper => {
+                            // The largest value below `upper` (at least one ulp below it):
+                            let delta = upper.abs().max(T::min_positive_value()) * T::epsilon();
+                            (upper - delta).max(lower)
+                        }
+                    });
+    }
+    breaks.push(sorted_values[nb_elem - 1]);
+    Ok(breaks)
+}
+
+/// Compute the "standard deviation" breaks on a list of sorted values: the breaks are
+/// spaced by `width` standard deviation from the mean, either with a break at the mean
+/// or, if `mean_centered` is true, with a class centered on the mean.
+///
+/// The number of class depends on the dispersion of the values: the breaks are
+/// computed until the minimum and the maximum, which are used as the first and last bounds.
+pub fn get_std_dev_breaks<T>(sorted_values: &[T],
+                             width: StdDevWidth,
+                             mean_centered: bool)
+                             -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, None)?;
+    let min = sorted_values[0];
+    let max = sorted_values[sorted_values.len() - 1];
+    let interval = T::from(width.factor()).unwrap() * standard_deviation(sorted_values)?;
+    let mut origin = mean(sorted_values)?;
+    if mean_centered {
+        origin += interval / T::from(2).unwrap();
+    }
+    let mut breaks = vec![min];
+    let mut i = ((min - origin) / interval).floor() + T::one();
+    let mut val = origin + i * interval;
+    while val < max {
+        if val > min {
+            breaks.push(val);
+        }
+        i += T::one();
+        val = origin + i * interval;
+    }
+    breaks.push(max);
+    Ok(breaks)
 }
@@ -0,0 +1,184 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::{BoundsInfo, Classification, IntervalClosure, MissingValues};
+use error::{ClassifError, ClassifResult};
+
+/// Builder allowing to set the options of a classification
+/// before computing it on some values.
+///
+/// All the options are validated when calling [`build`], before computing the bounds.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{Classifier, Classification, IntervalClosure, MissingValues};
+/// # fn main() {
+/// let values = [1.02, 1.3, 2.4, 5.0, 2.1, std::f64::NAN, 5.3, 4.0, 3.0, 1.3, 4.3, 6.07];
+/// let bounds_info = Classifier::new(Classification::EqualInterval)
+///     .classes(3)
+///     .missing_values(MissingValues::Drop)
+///     .closure(IntervalClosure::Left)
+///     .precision(2)
+///     .build(&values)
+///     .unwrap();
+/// assert_eq!(bounds_info.bounds, vec![1.0, 2.7, 4.4, 6.1]);
+/// assert_eq!(bounds_info.nb_missing, 1);
+/// # }
+/// ```
+/// [`build`]: struct.Classifier.html#method.build
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Classifier {
+    method: Classification,
+    nb_class: Option<u32>,
+    missing_values: MissingValues,
+    closure: IntervalClosure,
+    precision: Option<u32>,
+}
+
+impl Classifier {
+    /// Create a builder for a classification using the `method` method.
+    pub fn new(method: Classification) -> Self {
+        Classifier {
+            method,
+            nb_class: None,
+            missing_values: MissingValues::default(),
+            closure: IntervalClosure::default(),
+            precision: None,
+        }
+    }
+
+    /// Set the number of class, which is required by all the methods except
+    /// the Head-Tail (`HeadTail` and `HeadTailRatio`), Tail-Head and Standard Deviation
+    /// methods (where it is ignored). It is only indicative for the Pretty method.
+    pub fn classes(mut self, nb_class: u32) -> Self {
+        self.nb_class = Some(nb_class);
+        self
+    }
+
+    /// Set how to handle the missing values (NaN or infinite values) of the input
+    /// (they make the classification fail by default).
+    pub fn missing_values(mut self, missing_values: MissingValues) -> Self {
+        self.missing_values = missing_values;
+        self
+    }
+
+    /// Set the closure of the classes intervals (right-closed by default).
+    pub fn closure(mut self, closure: IntervalClosure) -> Self {
+        self.closure = closure;
+        self
+    }
+
+    /// Round the bounds to the given number of significant digits
+    /// (see [`BoundsInfo::round_bounds`]).
+    ///
+    /// [`BoundsInfo::round_bounds`]: struct.BoundsInfo.html#method.round_bounds
+    pub fn precision(mut self, significant_digits: u32) -> Self {
+        self.precision = Some(significant_digits);
+        self
+    }
+
+    // Check the options which don't depend on the values to classify.
+    fn check_options(&self) -> ClassifResult<()> {
+        match self.method {
+            Classification::HeadTail |
+            Classification::TailHead |
+            Classification::StandardDeviation { .. } => (),
+            Classification::HeadTailRatio { head_ratio, max_class } => {
+                if !(head_ratio > 0. && head_ratio < 1.) {
+                    return Err(ClassifError::InvalidParameter("head ratio"));
+                }
+                if let Some(nb_class) = max_class {
+                    if nb_class < 2 {
+                        return Err(ClassifError::InvalidClassNumber(nb_class));
+                    }
+                }
+            }
+            Classification::Manual => {
+                return Err(ClassifError::InvalidParameter("manual breaks have to be provided \
+                                                           using BoundsInfo::from_breaks"))
+            }
+            Classification::Custom { .. } => {
+                return Err(ClassifError::InvalidParameter("custom methods have to be used \
+                                                           with BoundsInfo::from_method"))
+            }
+            _ => {
+                let nb_class = match self.nb_class {
+                    Some(nb_class) => nb_class,
+                    None => return Err(ClassifError::InvalidParameter("number of class")),
+                };
+                let min_class = if self.method == Classification::Pretty { 1 } else { 2 };
+                if nb_class < min_class ||
+                   (self.method == Classification::NestedMeans && !nb_class.is_power_of_two()) {
+                    return Err(ClassifError::InvalidClassNumber(nb_class));
+                }
+                if let Classification::LogEqualInterval { base, .. } = self.method {
+                    if !(base > 0. && base != 1. && base.is_finite()) {
+                        return Err(ClassifError::InvalidParameter("logarithm base"));
+                    }
+                }
+            }
+        }
+        if self.precision == Some(0) {
+            return Err(ClassifError::InvalidParameter("precision"));
+        }
+        Ok(())
+    }
+
+    /// Compute the classification of `values` (which don't need to be sorted).
+    ///
+    /// Returns an error if one of the options is invalid, if there is less than two
+    /// values, if the number of class can't be computed on them, if the values contain
+    /// NaN or infinite values (unless allowed by the missing values policy)
+    /// or if all the values are equal.
+    pub fn build<T>(&self, values: &[T]) -> ClassifResult<BoundsInfo<T>>
+        where T: Float + NumAssignOps
+    {
+        self.check_options()?;
+        let bounds_info = BoundsInfo::compute(self.nb_class.unwrap_or(0),
+                                              values,
+                                              self.method.clone(),
+                                              self.missing_values)?;
+        Ok(self.finish(bounds_info, values))
+    }
+
+    /// Same as [`build`] on values which are already sorted in ascending order
+    /// (missing values, if allowed, being anywhere), avoiding to copy and to sort them.
+    ///
+    /// Returns `ClassifError::UnsortedValues` if the values are not sorted (which is checked
+    /// in linear time).
+    ///
+    /// [`build`]: struct.Classifier.html#method.build
+    pub fn build_sorted<T>(&self, sorted_values: &[T]) -> ClassifResult<BoundsInfo<T>>
+        where T: Float + NumAssignOps
+    {
+        self.check_options()?;
+        let finite_values;
+        let (sorted_values, nb_missing) = if sorted_values.iter().all(|v| v.is_finite()) {
+            (sorted_values, 0)
+        } else {
+            let (values, nb_missing) = self.missing_values.filter(sorted_values)?;
+            finite_values = values;
+            (&finite_values[..], nb_missing)
+        };
+        if sorted_values.windows(2).any(|w| w[0] > w[1]) {
+            return Err(ClassifError::UnsortedValues);
+        }
+        let bounds_info = BoundsInfo::compute_sorted(self.nb_class.unwrap_or(0),
+                                                     sorted_values,
+                                                     self.method.clone(),
+                                                     self.missing_values,
+                                                     nb_missing)?;
+        Ok(self.finish(bounds_info, sorted_values))
+    }
+
+    // Apply the options which don't change the computation of the bounds.
+    fn finish<T>(&self, mut bounds_info: BoundsInfo<T>, values: &[T]) -> BoundsInfo<T>
+        where T: Float + NumAssignOps
+    {
+        bounds_info.closure = self.closure;
+        if let Some(significant_digits) = self.precision {
+            bounds_info.round_bounds(values, significant_digits);
+        }
+        bounds_info
+    }
+}
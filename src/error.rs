@@ -0,0 +1,105 @@
+// `failure_derive` generates its impls inside an anonymous const item:
+#![allow(non_local_definitions)]
+use std::{self, fmt};
+use num_traits::Float;
+
+/// The error type returned by [`BoundsInfo::new`], the `get_*_breaks` functions
+/// and the functions of the [`stats`] module.
+///
+/// [`BoundsInfo::new`]: ../struct.BoundsInfo.html#method.new
+/// [`stats`]: ../stats/index.html
+#[derive(Fail, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ClassifError {
+    /// Not enough values were provided (number of values provided, number of values required).
+    #[fail(display = "Too small number of values ({}, at least {} required)", _0, _1)]
+    TooFewValues(usize, usize),
+    /// The requested number of class can't be computed on the input values.
+    #[fail(display = "Invalid number of class ({})", _0)]
+    InvalidClassNumber(u32),
+    /// The input values contain NaN or infinite value.
+    #[fail(display = "Input values contain NaN or infinite value")]
+    NonFiniteValue,
+    /// The input values contain zero or negative value.
+    #[fail(display = "{} requires only positive numbers as input", _0)]
+    OnlyPositive(MayFail),
+    /// A parameter of the classification method is invalid.
+    #[fail(display = "Invalid parameter: {}", _0)]
+    InvalidParameter(&'static str),
+    /// All the input values are equal.
+    #[fail(display = "All the input values are equal")]
+    DegenerateSerie,
+    /// The input values were expected to be sorted (in ascending order) but aren't.
+    #[fail(display = "Input values are not sorted")]
+    UnsortedValues,
+    /// Two inputs which should have the same length don't
+    /// (length of the first input, length of the second input).
+    #[fail(display = "Inputs of different lengths ({} and {})", _0, _1)]
+    LengthMismatch(usize, usize),
+}
+
+/// The computation which failed because of non-positive input values.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MayFail {
+    HarmonicMean,
+    GeometricMean,
+    GeometricBreaks,
+    LogEqualInterval,
+}
+
+impl std::fmt::Display for MayFail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub type ClassifResult<T> = std::result::Result<T, ClassifError>;
+
+/// Check that there is at least `min_len` values, that they are all finite
+/// and, if `nb_class` is provided, that this number of class can be computed on them.
+pub(crate) fn check_values<T>(values: &[T], min_len: usize, nb_class: Option<u32>) -> ClassifResult<()>
+    where T: Float
+{
+    let nb_elem = values.len();
+    if nb_elem < min_len {
+        return Err(ClassifError::TooFewValues(nb_elem, min_len));
+    }
+    if let Some(nb_class) = nb_class {
+        if nb_class < 2 || nb_class as usize > nb_elem {
+            return Err(ClassifError::InvalidClassNumber(nb_class));
+        }
+    }
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(ClassifError::NonFiniteValue);
+    }
+    Ok(())
+}
+
+/// Same as `check_values` but also reject series made of a single repeated value,
+/// on which no meaningful classification can be computed.
+pub(crate) fn check_classif_input<T>(values: &[T], nb_class: Option<u32>) -> ClassifResult<()>
+    where T: Float
+{
+    check_values(values, 2, nb_class)?;
+    let first = values[0];
+    if values.iter().all(|v| *v == first) {
+        return Err(ClassifError::DegenerateSerie);
+    }
+    Ok(())
+}
+
+/// Check that `weights` has the same length as `values`
+/// and contains only finite, positive or zero, weights (not all zero).
+pub(crate) fn check_weights<T>(values: &[T], weights: &[T]) -> ClassifResult<()>
+    where T: Float
+{
+    if weights.len() != values.len() {
+        return Err(ClassifError::LengthMismatch(values.len(), weights.len()));
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w < T::zero()) ||
+       weights.iter().all(|w| *w == T::zero()) {
+        return Err(ClassifError::InvalidParameter("weights have to be finite and positive"));
+    }
+    Ok(())
+}
@@ -1,81 +1,221 @@
-use num_traits::{Float, One, NumAssignOps};
+use num_traits::{Float, NumAssignOps};
+use error::{ClassifResult, check_classif_input, check_weights};
+use stats::mean;
 
-// Lets fake a 2D array (square shape matrix) with a 1D vector:
-struct Matrix<T> {
-    values: Vec<T>,
-    dim: usize,
+// Cumulative sums of the (weighted) values and of their squares, allowing to
+// compute the sum of squared deviations of any contiguous range in O(1).
+struct CumulativeSums<T> {
+    sum: Vec<T>,
+    sum_sq: Vec<T>,
+    // Cumulative sums of the weights, if any:
+    sum_w: Option<Vec<T>>,
 }
 
-// ... and implement getter and setter methods using 'unsafe' functions :
-impl<T: PartialEq + Clone> Matrix<T> {
-    pub fn new(init_value: T, dim: usize) -> Matrix<T> {
-        Matrix {
-            values: vec![init_value; dim * dim],
-            dim: dim,
+impl<T> CumulativeSums<T>
+    where T: Float + NumAssignOps
+{
+    pub fn new(sorted_values: &[T], weights: Option<&[T]>) -> CumulativeSums<T> {
+        let mut sum = Vec::with_capacity(sorted_values.len() + 1);
+        let mut sum_sq = Vec::with_capacity(sorted_values.len() + 1);
+        let (mut s1, mut s2) = (T::zero(), T::zero());
+        sum.push(s1);
+        sum_sq.push(s2);
+        for (i, &val) in sorted_values.iter().enumerate() {
+            let w = weights.map_or(T::one(), |w| w[i]);
+            s1 += w * val;
+            s2 += w * val * val;
+            sum.push(s1);
+            sum_sq.push(s2);
         }
+        let sum_w = weights.map(|weights| {
+            let mut sum_w = Vec::with_capacity(weights.len() + 1);
+            let mut s = T::zero();
+            sum_w.push(s);
+            for &w in weights {
+                s += w;
+                sum_w.push(s);
+            }
+            sum_w
+        });
+        CumulativeSums { sum, sum_sq, sum_w }
     }
 
+    // Sum of squared deviations of the values between index `i` and `j` (both included).
     #[inline(always)]
-    pub fn get(&self, ix: (usize, usize)) -> &T {
-        unsafe { self.values.get_unchecked(ix.0 * self.dim + ix.1) }
+    pub fn ssd(&self, i: usize, j: usize) -> T {
+        let s1 = self.sum[j + 1] - self.sum[i];
+        let s2 = self.sum_sq[j + 1] - self.sum_sq[i];
+        let w = match self.sum_w {
+            Some(ref sum_w) => sum_w[j + 1] - sum_w[i],
+            None => T::from(j - i + 1).unwrap(),
+        };
+        if w <= T::zero() {
+            return T::zero();
+        }
+        let v = s2 - (s1 * s1) / w;
+        if v < T::zero() { T::zero() } else { v }
     }
+}
 
-    #[inline(always)]
-    pub fn set(&mut self, ix: (usize, usize), value: T) {
-        let v = unsafe { self.values.get_unchecked_mut(ix.0 * self.dim + ix.1) };
-        *v = value;
+// Fill the `cost` row for the class `q` (for the indexes between `lo` and `hi`),
+// knowing that the optimal starting index of the last class lies between
+// `opt_lo` and `opt_hi` (the optimal starting index is monotone in the index
+// of the last value, which allows this divide and conquer approach).
+#[allow(clippy::too_many_arguments)]
+fn fill_row<T>(sums: &CumulativeSums<T>,
+               prev_cost: &[T],
+               cost: &mut [T],
+               backtrack: &mut [usize],
+               q: usize,
+               lo: usize,
+               hi: usize,
+               opt_lo: usize,
+               opt_hi: usize)
+    where T: Float + NumAssignOps
+{
+    if lo > hi {
+        return;
+    }
+    let mid = (lo + hi) / 2;
+    let start = if opt_lo > q { opt_lo } else { q };
+    let end = if opt_hi < mid { opt_hi } else { mid };
+    let mut best = start;
+    let mut best_cost = Float::max_value();
+    for j in start..end + 1 {
+        let c = prev_cost[j - 1] + sums.ssd(j, mid);
+        if c < best_cost {
+            best_cost = c;
+            best = j;
+        }
     }
+    cost[mid] = best_cost;
+    backtrack[mid] = best;
+    if mid > lo {
+        fill_row(sums, prev_cost, cost, backtrack, q, lo, mid - 1, opt_lo, best);
+    }
+    fill_row(sums, prev_cost, cost, backtrack, q, mid + 1, hi, best, opt_hi);
 }
 
-/// Compute the "Natural Breaks" on a list of sorted values, based on Jenks optimization.
-pub fn get_jenks_breaks<T>(sorted_values: &[T], nb_class: u32) -> Vec<T>
+// Returns the starting index of each class of the partition of the sorted values in
+// `k` classes minimizing the sum of squared deviations within classes.
+//
+// This optimal partition is found by dynamic programming, as in *Ckmeans.1d.dp*,
+// in O(k·n·log(n)) time and O(k·n) memory, n being the number of values.
+fn optimal_class_starts<T>(sorted_values: &[T], weights: Option<&[T]>, k: usize) -> Vec<usize>
     where T: Float + NumAssignOps
 {
-    let k: usize = nb_class as usize;
     let nb_elem: usize = sorted_values.len();
-    let mut v1 = Matrix::new(1, nb_elem);
-    let mut v2 = Matrix::new(Float::max_value(), nb_elem);
-
-    let (mut v, mut val, mut s1, mut s2, mut w, mut i3, mut i4): (T, T, T, T, T, usize, usize);
+    let sums = CumulativeSums::new(sorted_values, weights);
 
-    for l in 2..(nb_elem + 1) {
-        s1 = T::zero();
-        s2 = T::zero();
-        w = T::zero();
-        for m in 1..(l + 1) {
-            i3 = l - m + 1;
-            val = unsafe { *sorted_values.get_unchecked(i3 - 1) };
-            s2 += val * val;
-            s1 += val;
-            w += One::one();
-            v = s2 - (s1 * s1) / w;
-            i4 = i3 - 1;
-            if i4 != 0 {
-                for j in 2..k + 1 {
-                    let _v = v + *v2.get((i4 - 1, j - 2));
-                    if *v2.get((l - 1, j - 1)) >= _v {
-                        v2.set((l - 1, j - 1), _v);
-                        v1.set((l - 1, j - 1), i3);
-                    }
-                }
-            }
-            v1.set((l - 1, 0), 1);
-            v2.set((l - 1, 0), v);
-        }
+    // `backtrack[q * nb_elem + i]` is the starting index of the last class
+    // when the values up to the index `i` are partitioned in `q + 1` classes:
+    let mut backtrack = vec![0usize; k * nb_elem];
+    let mut prev_cost = (0..nb_elem).map(|i| sums.ssd(0, i)).collect::<Vec<T>>();
+    let mut cost = vec![Float::max_value(); nb_elem];
+    for q in 1..k {
+        fill_row(&sums,
+                 &prev_cost,
+                 &mut cost,
+                 &mut backtrack[q * nb_elem..(q + 1) * nb_elem],
+                 q,
+                 q,
+                 nb_elem - 1,
+                 q,
+                 nb_elem - 1);
+        ::std::mem::swap(&mut prev_cost, &mut cost);
     }
+
     let mut kclass = vec![0usize; k];
-    let mut n = nb_elem;
-    let mut j = k;
-    while j > 1 {
-        n = *v1.get(((n - 1), (j - 1))) - 1usize;
-        kclass[(j - 2)] = n;
-        j -= 1;
+    let mut n = nb_elem - 1;
+    for q in (1..k).rev() {
+        let start = backtrack[q * nb_elem + n];
+        kclass[q] = start;
+        n = start - 1;
     }
-    let mut breaks = Vec::with_capacity(k);
+    kclass
+}
+
+/// Compute the "Natural Breaks" on a list of sorted values, based on Jenks optimization.
+///
+/// The optimal partition (minimizing the sum of squared deviations within classes)
+/// is found by dynamic programming, as in *Ckmeans.1d.dp*, in O(k·n·log(n)) time and
+/// O(k·n) memory, k being the number of classes and n the number of values.
+pub fn get_jenks_breaks<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    let kclass = optimal_class_starts(sorted_values, None, nb_class as usize);
+    Ok(breaks_from_class_starts(sorted_values, &kclass))
+}
+
+/// Compute the "Natural Breaks" on a list of sorted values, each value having a weight
+/// (such as the population or the area of a feature), which minimize the weighted sum
+/// of squared deviations within classes.
+///
+/// `weights` is a list of positive (or zero) weights, in the same order as `sorted_values`.
+pub fn get_weighted_jenks_breaks<T>(sorted_values: &[T],
+                                    weights: &[T],
+                                    nb_class: u32)
+                                    -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    check_weights(sorted_values, weights)?;
+    let kclass = optimal_class_starts(sorted_values, Some(weights), nb_class as usize);
+    Ok(breaks_from_class_starts(sorted_values, &kclass))
+}
+
+// The breaks are the minimum, the highest value of each class but the last one, and the maximum.
+fn breaks_from_class_starts<T: Float>(sorted_values: &[T], kclass: &[usize]) -> Vec<T> {
+    let mut breaks = Vec::with_capacity(kclass.len() + 1);
     breaks.push(sorted_values[0]);
-    for i in 1..k {
-        breaks.push(sorted_values[(kclass[i - 1usize] - 1)]);
+    for &start in &kclass[1..] {
+        breaks.push(sorted_values[start - 1]);
     }
-    breaks.push(sorted_values[(nb_elem - 1)]);
+    breaks.push(sorted_values[sorted_values.len() - 1]);
     breaks
 }
+
+/// The clusters computed by the optimal one-dimensional k-means method.
+#[derive(Clone, PartialEq, Debug)]
+pub struct KMeansClusters<T> {
+    /// The breaks: the minimum, the midpoints between consecutive clusters and the maximum.
+    pub breaks: Vec<T>,
+    /// The center (ie. the mean value) of each cluster.
+    pub centers: Vec<T>,
+    /// The lowest and the highest value of each cluster.
+    pub boundaries: Vec<(T, T)>,
+}
+
+/// Compute the optimal one-dimensional k-means clusters of a list of sorted values
+/// (using the *Ckmeans* method of Wang & Song).
+///
+/// The partition is the same as the one of the "Natural Breaks" method but the breaks
+/// are placed halfway between consecutive clusters instead of on their highest value.
+pub fn get_kmeans_clusters<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<KMeansClusters<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, Some(nb_class))?;
+    let nb_elem: usize = sorted_values.len();
+    let mut kclass = optimal_class_starts(sorted_values, None, nb_class as usize);
+    kclass.push(nb_elem);
+    let two = T::from(2).unwrap();
+    let mut breaks = Vec::with_capacity(kclass.len());
+    let mut centers = Vec::with_capacity(nb_class as usize);
+    let mut boundaries = Vec::with_capacity(nb_class as usize);
+    breaks.push(sorted_values[0]);
+    for w in kclass.windows(2) {
+        let cluster = &sorted_values[w[0]..w[1]];
+        centers.push(mean(cluster)?);
+        boundaries.push((cluster[0], cluster[cluster.len() - 1]));
+        if w[1] < nb_elem {
+            breaks.push((cluster[cluster.len() - 1] + sorted_values[w[1]]) / two);
+        }
+    }
+    breaks.push(sorted_values[nb_elem - 1]);
+    Ok(KMeansClusters {
+           breaks,
+           centers,
+           boundaries,
+       })
+}
@@ -4,55 +4,97 @@
 //!
 //! This library allows to computed break values according to a few methods (listed in
 //! the [`Classification`] Enum).
-//! Theses break values can be computed by creating a new [`BoundsInfo`] struct.
+//! Theses break values can be computed by creating a new [`BoundsInfo`] struct, either
+//! directly or using a [`Classifier`] to set the options of the classification.
 //!
-//! If the input values are already sorted and you don't care about error checking
-//! you can directly use the `get_{jenks|quantiles|etc...}_breaks` functions.
+//! If the input values are already sorted you can directly use
+//! the `get_{jenks|quantiles|etc...}_breaks` functions.
+//!
+//! All these functions report invalid input (too few values, invalid number of class,
+//! NaN or infinite values, etc.) using the [`ClassifError`] Enum.
 //!
 //! This library also provide a few basic statistical functionnalities, allowing to
 //! compute mean value, kurtosis value, standard deviation, variance, root of mean square, etc.
 //!
+//! ## Serialization
+//!
+//! With the optional `serde` feature, [`BoundsInfo`], [`Classification`] and the types
+//! of their parameters implement serde's `Serialize` and `Deserialize` traits.
+//! The schema follows serde's default representation (externally tagged enums, named
+//! after their variants) and is considered stable; in JSON, a `BoundsInfo` looks like:
+//!
+//! ```json
+//! {
+//!   "type_classif": { "StandardDeviation": { "width": "Half", "mean_centered": false } },
+//!   "nb_class": 2,
+//!   "bounds": [1.0, 2.5, 4.0],
+//!   "min": 1.0,
+//!   "max": 4.0,
+//!   "mean": 2.5,
+//!   "ht_index": null,
+//!   "centers": null,
+//!   "missing_values": "Error",
+//!   "nb_missing": 0,
+//!   "closure": "Right"
+//! }
+//! ```
+//!
+//! Parameterless classification methods are serialized as a plain string (such as
+//! `"JenksNaturalBreaks"`). The `ht_index`, `centers`, `missing_values`, `nb_missing`
+//! and `closure` fields may be omitted, their default value being then used.
+//! Deserializing a `BoundsInfo` fails if its bounds are not finite and sorted or if
+//! there isn't `nb_class + 1` of them.
+//!
 //! [`Classification`]: enum.Classification.html
 //! [`BoundsInfo`]: struct.BoundsInfo.html
-#[macro_use]
+//! [`Classifier`]: struct.Classifier.html
+//! [`ClassifError`]: enum.ClassifError.html
+#[cfg_attr(test, macro_use)]
 extern crate assert_approx_eq;
 extern crate num_traits;
 extern crate failure;
 #[macro_use] extern crate failure_derive;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 /// Basic statistical functionnalities: mean, standard deviation, kurtosis, variance, etc.
+///
+/// The same statistics can be computed in a single pass, on values received
+/// one by one, using an [`OnlineStats`] accumulator.
+///
+/// These functions fail on NaN or infinite values, which can be put aside
+/// beforehand using [`MissingValues::filter`].
+///
+/// [`MissingValues::filter`]: ../enum.MissingValues.html#method.filter
+/// [`OnlineStats`]: struct.OnlineStats.html
 pub mod stats;
 
+mod error;
 mod jenks;
 mod classif;
+mod classifier;
+mod method;
+mod quality;
+mod optimal;
+mod pretty;
+mod sketch;
+
+pub use error::{ClassifError, ClassifResult, MayFail};
+pub use classif::{Classification, BoundsInfo, BreakPlacement, IntervalClosure, MissingValues,
+                  StdDevWidth};
+pub use classifier::Classifier;
+pub use method::BreaksMethod;
+pub use quality::ClassStats;
+pub use optimal::{select_nb_class, NbClassCriterion, NbClassScore, NbClassSelection};
+pub use jenks::{get_jenks_breaks, get_weighted_jenks_breaks, get_kmeans_clusters, KMeansClusters};
+pub use pretty::get_pretty_breaks;
+pub use sketch::QuantileSketch;
+pub use classif::{get_quantiles, get_quantiles_with_method, get_weighted_quantiles, get_equal_interval, get_head_tail_breaks, get_head_tail_ratio_breaks,
+                  get_ht_index, get_tail_head_breaks,
+                  get_arithmetic_breaks, get_geometric_breaks, get_log_equal_interval,
+                  get_nested_means_breaks, get_maximum_breaks, get_std_dev_breaks};
 
-pub use classif::{Classification, BoundsInfo};
-pub use jenks::get_jenks_breaks;
-pub use classif::{get_quantiles, get_equal_interval, get_head_tail_breaks, get_tail_head_breaks,
-                  get_arithmetic_breaks};
-
-
-mod error {
-    use std::{self, fmt};
-    #[derive(Fail, Debug)]
-    pub enum ClassifError {
-      #[fail(display = "{} requires only positive numbers as input", _0)]
-      OnlyPositive(MayFail),
-      #[fail(display = "An unknown error has occurred.")]
-      UnknownError,
-    }
-    #[derive(Debug)]
-    pub enum MayFail {
-        HarmonicMean,
-        GeometricMean,
-    }
-    impl std::fmt::Display for MayFail {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "{}", format!("{:?}", self))
-        }
-    }
-    pub type ClassifResult<T> = std::result::Result<T, ClassifError>;
-}
 
 #[cfg(test)]
 mod tests {
@@ -71,8 +113,57 @@ mod tests {
         let b = BoundsInfo::new(4, &values, Classification::HeadTail).unwrap();
         assert_eq!(b.bounds.as_slice(), [1., 7., 9.090909090909092, 11., 12.]);
         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let breaks = get_head_tail_breaks(&mut values);
+        let breaks = get_head_tail_breaks(&values).unwrap();
         assert_eq!(breaks.as_slice(), [1., 7., 9.090909090909092, 11., 12.]);
+        // Repeated extreme values:
+        let b = BoundsInfo::new(0, &[1., 2., 3., 3.], Classification::HeadTail).unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 3.]);
+        let b = BoundsInfo::new(0, &[1., 1., 2., 3.], Classification::TailHead).unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 3.]);
+    }
+
+    #[test]
+    fn test_nested_means() {
+        let mut values = get_test_values();
+        let b = BoundsInfo::new(4, &values, Classification::NestedMeans).unwrap();
+        assert_eq!(b.bounds.as_slice(), [1.0, 1.9166666666666667, 3.789473684210526, 7.0, 12.0]);
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let breaks = get_nested_means_breaks(&values, 3).unwrap();
+        assert_eq!(breaks.as_slice(),
+                   [1.0,
+                    1.0,
+                    1.9166666666666667,
+                    2.466666666666667,
+                    3.789473684210526,
+                    5.647058823529412,
+                    7.0,
+                    9.090909090909092,
+                    12.0]);
+        assert_eq!(BoundsInfo::new(6, &values, Classification::NestedMeans).err(),
+                   Some(ClassifError::InvalidClassNumber(6)));
+    }
+
+    #[test]
+    fn test_head_tail_ratio() {
+        let mut values = get_test_values();
+        let ht = Classification::HeadTailRatio {
+            head_ratio: 0.4,
+            max_class: None,
+        };
+        let b = BoundsInfo::new(0, &values, ht).unwrap();
+        assert_eq!(b.bounds.as_slice(),
+                   [1.0, 3.789473684210526, 7.0, 9.090909090909092, 11.0, 12.0]);
+        assert_eq!(b.ht_index, Some(5));
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        // Limited number of class:
+        let breaks = get_head_tail_ratio_breaks(&values, 0.4, Some(3)).unwrap();
+        assert_eq!(breaks.as_slice(), [1.0, 3.789473684210526, 7.0, 12.0]);
+        assert_eq!(get_ht_index(&values, 0.4).unwrap(), 5);
+        // The first head (28 values out of 76) is too large:
+        let breaks = get_head_tail_ratio_breaks(&values, 0.2, None).unwrap();
+        assert_eq!(breaks.as_slice(), [1.0, 12.0]);
+        assert_eq!(get_head_tail_ratio_breaks(&values, 1.2, None).err(),
+                   Some(ClassifError::InvalidParameter("head ratio")));
     }
 
     #[test]
@@ -81,27 +172,433 @@ mod tests {
         let b = BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).unwrap();
         assert_eq!(b.bounds.as_slice(), [1.0, 2.0, 4.0, 7.0, 9.0, 12.0]);
         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let breaks = get_jenks_breaks(&values, 5);
+        let breaks = get_jenks_breaks(&values, 5).unwrap();
         assert_eq!(breaks.as_slice(), [1.0, 2.0, 4.0, 7.0, 9.0, 12.0]);
     }
 
+    #[test]
+    fn test_jenks_breaks_larger_serie() {
+        let mut values = (0..300u64)
+            .map(|i| ((i * i * 7 + 3 * i) % 101) as f64 * 1.5)
+            .collect::<Vec<f64>>();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let breaks = get_jenks_breaks(&values, 7).unwrap();
+        assert_eq!(breaks.as_slice(), [0.0, 16.5, 34.5, 52.5, 81.0, 96.0, 118.5, 150.0]);
+        // A single value can make up the first class:
+        let breaks = get_jenks_breaks(&[1., 40., 60., 680.], 3).unwrap();
+        assert_eq!(breaks.as_slice(), [1., 1., 60., 680.]);
+    }
+
+    #[test]
+    fn test_kmeans_clusters() {
+        let mut values = get_test_values();
+        let b = BoundsInfo::new(5, &values, Classification::KMeans).unwrap();
+        assert_eq!(b.bounds.as_slice(), [1.0, 2.5, 4.5, 7.5, 9.5, 12.0]);
+        assert_eq!(b.centers.unwrap().len(), 5);
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let clusters = get_kmeans_clusters(&values, 5).unwrap();
+        assert_eq!(clusters.breaks.as_slice(), [1.0, 2.5, 4.5, 7.5, 9.5, 12.0]);
+        assert_eq!(clusters.boundaries[4], (10., 12.));
+        assert_eq!(clusters.centers[4], 11.);
+        assert_eq!(BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks)
+                       .unwrap()
+                       .centers,
+                   None);
+    }
+
     #[test]
     fn test_quantiles_breaks() {
         let mut values = get_test_values();
         let b = BoundsInfo::new(4, &values, Classification::Quantiles).unwrap();
         assert_eq!(b.bounds.as_slice(), [1., 2., 3., 6., 12.]);
         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let breaks = get_quantiles(&values, 4);
+        let breaks = get_quantiles(&values, 4).unwrap();
         assert_eq!(breaks.as_slice(), [1., 2., 3., 6., 12.]);
     }
 
+    #[test]
+    fn test_classify() {
+        let values = get_test_values();
+        let b = BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).unwrap();
+        let classes = b.classify(&values);
+        for (v, c) in values.iter().zip(&classes) {
+            assert_eq!(*c, b.get_class_index(*v));
+        }
+        assert_eq!(b.classify(&[0., 2., 2.5, 12., 13.]),
+                   vec![None, Some(0), Some(1), Some(4), None]);
+        let mut out = vec![0; 3];
+        b.classify_into(&[0., 4., 13.], &mut out, 255).unwrap();
+        assert_eq!(out, vec![255, 1, 255]);
+        assert_eq!(b.classify_into(&[0., 4.], &mut out, 255).err(),
+                   Some(ClassifError::LengthMismatch(2, 3)));
+        let b = BoundsInfo::from_breaks(&[1., 2., 3.], &[1., 3.], false, MissingValues::Error)
+            .unwrap();
+        assert_eq!(b.classify(&[1., 2., 2.5]), vec![Some(0), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_interval_closure() {
+        let values = get_test_values();
+        let mut b = BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).unwrap();
+        assert_eq!(b.closure, IntervalClosure::Right);
+        assert_eq!(b.classify(&[1., 2., 4., 12.]), vec![Some(0), Some(0), Some(1), Some(4)]);
+        assert_eq!(b.class_counts(&values), vec![34, 18, 13, 8, 3]);
+        b.closure = IntervalClosure::Left;
+        assert_eq!(b.classify(&[1., 2., 4., 12.]), vec![Some(0), Some(1), Some(2), Some(4)]);
+        let mut out = vec![0; 2];
+        b.classify_into(&[9., 13.], &mut out, 255).unwrap();
+        assert_eq!(out, vec![4, 255]);
+        assert_eq!(b.class_counts(&values).iter().sum::<usize>(), values.len());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        extern crate serde_json;
+        let values = get_test_values();
+        for type_classif in [Classification::JenksNaturalBreaks,
+                             Classification::KMeans,
+                             Classification::HeadTailRatio {
+                                 head_ratio: 0.4,
+                                 max_class: Some(4),
+                             },
+                             Classification::StandardDeviation {
+                                 width: StdDevWidth::Half,
+                                 mean_centered: true,
+                             }] {
+            let mut b = BoundsInfo::with_missing_values(5, &values, type_classif,
+                                                        MissingValues::NoDataClass)
+                    .unwrap();
+            b.closure = IntervalClosure::Left;
+            let json = serde_json::to_string(&b).unwrap();
+            let b2: BoundsInfo<f64> = serde_json::from_str(&json).unwrap();
+            assert_eq!(b, b2);
+        }
+        assert_eq!(serde_json::to_string(&Classification::Quantiles).unwrap(),
+                   "\"Quantiles\"");
+        assert_eq!(serde_json::to_string(&Classification::MaximumBreaks {
+                                              placement: BreakPlacement::Lower,
+                                          })
+                           .unwrap(),
+                   r#"{"MaximumBreaks":{"placement":"Lower"}}"#);
+        // Optional fields can be omitted:
+        let b: BoundsInfo<f64> = serde_json::from_str(r#"{"type_classif":"Manual","nb_class":1,
+            "bounds":[1.0,2.0],"min":1.0,"max":2.0,"mean":1.5}"#)
+                .unwrap();
+        assert_eq!(b.closure, IntervalClosure::Right);
+        assert_eq!(b.get_class_index(1.5), Some(0));
+        // Inconsistent bounds are rejected:
+        for json in [r#"{"type_classif":"Manual","nb_class":1,"bounds":[],"min":1.0,"max":2.0,"mean":1.5}"#,
+                     r#"{"type_classif":"Manual","nb_class":1,"bounds":[2.0,1.0],"min":1.0,"max":2.0,"mean":1.5}"#,
+                     r#"{"type_classif":"Manual","nb_class":3,"bounds":[1.0,2.0],"min":1.0,"max":2.0,"mean":1.5}"#] {
+            assert!(serde_json::from_str::<BoundsInfo<f64>>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_classifier_builder() {
+        let values = get_test_values();
+        let b = Classifier::new(Classification::JenksNaturalBreaks)
+            .classes(5)
+            .build(&values)
+            .unwrap();
+        assert_eq!(b, BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).unwrap());
+        let b = Classifier::new(Classification::Quantiles)
+            .classes(3)
+            .closure(IntervalClosure::Left)
+            .precision(1)
+            .build(&values)
+            .unwrap();
+        assert_eq!(b.closure, IntervalClosure::Left);
+        assert_eq!(b.bounds.as_slice(), [1., 2., 4., 20.]);
+        // Options are checked before the values:
+        assert_eq!(Classifier::new(Classification::Quantiles).build::<f64>(&[]).err(),
+                   Some(ClassifError::InvalidParameter("number of class")));
+        let ht = Classification::HeadTailRatio {
+            head_ratio: 1.5,
+            max_class: None,
+        };
+        assert_eq!(Classifier::new(ht).build::<f64>(&[]).err(),
+                   Some(ClassifError::InvalidParameter("head ratio")));
+        assert_eq!(Classifier::new(Classification::EqualInterval)
+                       .classes(3)
+                       .precision(0)
+                       .build(&values)
+                       .err(),
+                   Some(ClassifError::InvalidParameter("precision")));
+        // The number of class is ignored by some methods:
+        assert!(Classifier::new(Classification::HeadTail).build(&values).is_ok());
+    }
+
+    #[test]
+    fn test_from_sorted() {
+        let values = get_test_values();
+        let mut sorted_values = values;
+        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for type_classif in [Classification::JenksNaturalBreaks,
+                             Classification::Quantiles,
+                             Classification::HeadTail] {
+            assert_eq!(BoundsInfo::from_sorted(4, &sorted_values, type_classif.clone()).unwrap(),
+                       BoundsInfo::new(4, &values, type_classif).unwrap());
+        }
+        assert_eq!(BoundsInfo::from_sorted(4, &values, Classification::Quantiles).err(),
+                   Some(ClassifError::UnsortedValues));
+        assert_eq!(BoundsInfo::from_sorted(2, &[1., f64::NAN, 2.], Classification::Quantiles).err(),
+                   Some(ClassifError::NonFiniteValue));
+        let b = Classifier::new(Classification::Quantiles)
+            .classes(2)
+            .missing_values(MissingValues::Drop)
+            .build_sorted(&[1., f64::NAN, 2., 3., 4.])
+            .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 2., 4.]);
+        assert_eq!(b.nb_missing, 1);
+    }
+
+    #[test]
+    fn test_quantile_sketch() {
+        // Small series are kept entirely, so that the breaks are exact:
+        let mut values = get_test_values();
+        let mut sketch = QuantileSketch::new();
+        sketch.extend(&values).unwrap();
+        let b = BoundsInfo::from_sketch(&sketch, 4).unwrap();
+        assert_eq!(b, BoundsInfo::new(4, &values, Classification::Quantiles).unwrap());
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(sketch.quantile_breaks(7).unwrap(), get_quantiles(&values, 7).unwrap());
+
+        // On larger series, the rank of each break is close to the exact one:
+        let values = (0..50000u64)
+            .map(|i| ((i * i * 7 + 3 * i) % 10007) as f64 / 3.)
+            .collect::<Vec<f64>>();
+        let mut sketch = QuantileSketch::new();
+        let mut other = QuantileSketch::new();
+        sketch.extend(&values[..20000]).unwrap();
+        other.extend(&values[20000..]).unwrap();
+        sketch.merge(&other);
+        assert_eq!(sketch.count(), values.len());
+        let mut sorted_values = values.clone();
+        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let exact_breaks = get_quantiles(&sorted_values, 10).unwrap();
+        let breaks = BoundsInfo::from_sketch(&sketch, 10).unwrap().bounds;
+        let max_error = sketch.normalized_rank_error() * values.len() as f64;
+        for (b, exact) in breaks.iter().zip(&exact_breaks) {
+            let rank = sorted_values.partition_point(|v| v <= b) as f64;
+            let exact_rank = sorted_values.partition_point(|v| v <= exact) as f64;
+            assert!((rank - exact_rank).abs() <= max_error);
+        }
+        assert_eq!(breaks[0], 0.);
+        assert_eq!(breaks[10], sorted_values[values.len() - 1]);
+        assert_eq!(QuantileSketch::<f64>::new().quantile_breaks(2).err(),
+                   Some(ClassifError::TooFewValues(0, 2)));
+        assert_eq!(sketch.push(f64::NAN).err(), Some(ClassifError::NonFiniteValue));
+    }
+
+    #[test]
+    fn test_breaks_method() {
+        struct EveryOtherValue;
+
+        impl BreaksMethod<f64> for EveryOtherValue {
+            fn name(&self) -> String {
+                "every_other_value".to_string()
+            }
+
+            fn breaks(&self, sorted_values: &[f64], _nb_class: u32) -> ClassifResult<Vec<f64>> {
+                Ok(sorted_values.iter().step_by(2).cloned().collect())
+            }
+        }
+
+        let values = [5., 1., 4., 2., 3.];
+        let b = BoundsInfo::from_method(&EveryOtherValue, 0, &values, MissingValues::Error).unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 3., 5.]);
+        assert_eq!(b.nb_class, 2);
+        assert_eq!(b.type_classif.to_string(), "custom(every_other_value)");
+        assert_eq!(b.type_classif.to_string().parse(), Ok(b.type_classif.clone()));
+        assert_eq!(BoundsInfo::new(2, &values, b.type_classif).err(),
+                   Some(ClassifError::InvalidParameter("custom methods have to be used with \
+                                                        BoundsInfo::from_method")));
+        // The built-in methods implement the trait too:
+        let values = get_test_values();
+        let methods: Vec<Box<dyn BreaksMethod<f64>>> = vec![Box::new(Classification::KMeans),
+                                                            Box::new(EveryOtherValue)];
+        for method in &methods {
+            let b = BoundsInfo::from_method(method.as_ref(), 5, &values, MissingValues::Error)
+                .unwrap();
+            assert_eq!(b.type_classif, method.classification());
+        }
+        let b = BoundsInfo::from_method(&Classification::KMeans, 5, &values, MissingValues::Error)
+            .unwrap();
+        assert_eq!(b, BoundsInfo::new(5, &values, Classification::KMeans).unwrap());
+        // The missing values policy applies to the custom methods too:
+        let values = [5., 1., f64::NAN, 4., 2., 3.];
+        assert_eq!(BoundsInfo::from_method(&EveryOtherValue, 0, &values, MissingValues::Error)
+                       .err(),
+                   Some(ClassifError::NonFiniteValue));
+        let b = BoundsInfo::from_method(&EveryOtherValue, 0, &values, MissingValues::NoDataClass)
+            .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 3., 5.]);
+        assert_eq!(b.get_class_index(f64::NAN), Some(2));
+        // The breaks of a custom method are used whatever its recorded classification:
+        struct Minmax;
+
+        impl BreaksMethod<f64> for Minmax {
+            fn name(&self) -> String {
+                "minmax".to_string()
+            }
+
+            fn breaks(&self, sorted_values: &[f64], _nb_class: u32) -> ClassifResult<Vec<f64>> {
+                Ok(vec![sorted_values[0], sorted_values[sorted_values.len() - 1]])
+            }
+
+            fn classification(&self) -> Classification {
+                Classification::Quantiles
+            }
+        }
+
+        let values = get_test_values();
+        let b = BoundsInfo::from_method(&Minmax, 4, &values, MissingValues::Error).unwrap();
+        assert_eq!(b.type_classif, Classification::Quantiles);
+        assert_eq!(b.bounds.as_slice(), [1., 12.]);
+    }
+
+    #[test]
+    fn test_classification_names() {
+        let methods = [Classification::EqualInterval,
+                       Classification::HeadTail,
+                       Classification::HeadTailRatio {
+                           head_ratio: 0.45,
+                           max_class: Some(5),
+                       },
+                       Classification::TailHead,
+                       Classification::NestedMeans,
+                       Classification::JenksNaturalBreaks,
+                       Classification::KMeans,
+                       Classification::Quantiles,
+                       Classification::Arithmetic,
+                       Classification::Geometric,
+                       Classification::Pretty,
+                       Classification::LogEqualInterval {
+                           base: 2.,
+                           log1p: true,
+                       },
+                       Classification::Manual,
+                       Classification::MaximumBreaks { placement: BreakPlacement::Upper },
+                       Classification::StandardDeviation {
+                           width: StdDevWidth::Quarter,
+                           mean_centered: true,
+                       }];
+        for method in &methods {
+            assert_eq!(method.to_string().parse::<Classification>().as_ref(), Ok(method));
+        }
+        assert_eq!("head_tail(0.4)".parse(),
+                   Ok(Classification::HeadTailRatio {
+                          head_ratio: 0.4,
+                          max_class: None,
+                      }));
+        assert_eq!("Std-Dev( 0.5 )".parse(),
+                   Ok(Classification::StandardDeviation {
+                          width: StdDevWidth::Half,
+                          mean_centered: false,
+                      }));
+        assert_eq!("maximum_breaks(Lower)".parse(),
+                   Ok(Classification::MaximumBreaks { placement: BreakPlacement::Lower }));
+        // Names used by the previous versions are still accepted:
+        for &(name, ref method) in &[("JenksNaturalBreaks", Classification::JenksNaturalBreaks),
+                                     ("jenks", Classification::JenksNaturalBreaks),
+                                     ("NATURAL_BREAKS", Classification::JenksNaturalBreaks),
+                                     ("quantile", Classification::Quantiles),
+                                     ("EqualInterval", Classification::EqualInterval),
+                                     ("EqualInverval", Classification::EqualInterval)] {
+            assert_eq!(name.parse::<Classification>().as_ref(), Ok(method));
+        }
+        assert_eq!("HeadTailRatio".parse(),
+                   Ok(Classification::HeadTailRatio {
+                          head_ratio: 0.4,
+                          max_class: None,
+                      }));
+        assert!("jenks(3)".parse::<Classification>().is_err());
+        assert!("std_dev(0.3)".parse::<Classification>().is_err());
+        assert!("head_tail(0.4".parse::<Classification>().is_err());
+        assert!("foo".parse::<Classification>().is_err());
+    }
+
+    #[test]
+    fn test_quantile_methods() {
+        use stats::QuantileMethod::*;
+        let values = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+        let expected = [(Type1, 3.), (Type2, 3.), (Type3, 2.), (Type4, 2.5), (Type5, 3.),
+                        (Type6, 2.75), (Type7, 3.25)];
+        for &(method, q) in &expected {
+            assert_eq!(stats::quantile(&values, 0.25, method).unwrap(), q);
+        }
+        assert_approx_eq!(stats::quantile(&values, 0.25, Type8).unwrap(), 2.916666666666667);
+        assert_approx_eq!(stats::quantile(&values, 0.25, Type9).unwrap(), 2.9375);
+        assert_eq!(stats::quantile(&values, 0.5, Type2).unwrap(), 5.5);
+        assert_eq!(stats::percentile(&values, 100., Type1).unwrap(), 10.);
+        assert!(stats::quantile(&values, 1.5, Type7).is_err());
+        // The legacy rule is still the one used by default:
+        let mut values = get_test_values();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(get_quantiles_with_method(&values, 4, Legacy).unwrap(),
+                   get_quantiles(&values, 4).unwrap());
+        assert_eq!(get_quantiles_with_method(&values, 4, Default::default()).unwrap(),
+                   get_quantiles(&values, 4).unwrap());
+        assert_eq!(get_quantiles_with_method(&values, 4, Type7).unwrap().as_slice(),
+                   [1., 2., 3., 6., 12.]);
+    }
+
+    #[test]
+    fn test_weighted_breaks() {
+        let values = get_test_values();
+        let weights = vec![1.; values.len()];
+        let b = BoundsInfo::with_weights(5,
+                                         &values,
+                                         &weights,
+                                         Classification::JenksNaturalBreaks,
+                                         MissingValues::Error)
+                .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1.0, 2.0, 4.0, 7.0, 9.0, 12.0]);
+        assert_approx_eq!(b.mean, stats::mean(&values).unwrap());
+        let breaks = get_weighted_quantiles(&[1., 2., 3., 4.], &[1., 1., 1., 3.], 2).unwrap();
+        assert_eq!(breaks.as_slice(), [1., 3., 4.]);
+        let breaks = get_weighted_jenks_breaks(&[1., 2., 10., 11.], &[1., 10., 10., 1.], 2).unwrap();
+        assert_eq!(breaks.as_slice(), [1., 2., 11.]);
+        assert_eq!(stats::weighted_mean(&[1., 2.], &[3., 1.]).unwrap(), 1.25);
+        assert_eq!(stats::weighted_variance(&[1., 3.], &[1., 1.]).unwrap(), 1.);
+        assert_eq!(stats::weighted_mean(&[1., 2.], &[1.]).err(),
+                   Some(ClassifError::LengthMismatch(2, 1)));
+        assert!(BoundsInfo::with_weights(2,
+                                         &values,
+                                         &weights,
+                                         Classification::HeadTail,
+                                         MissingValues::Error)
+                        .is_err());
+        // The weights of the missing values are ignored:
+        let values = [1., 2., f64::NAN, 3., 4.];
+        let weights = [1., 1., f64::NAN, 1., 3.];
+        assert_eq!(BoundsInfo::with_weights(2,
+                                            &values,
+                                            &weights,
+                                            Classification::Quantiles,
+                                            MissingValues::Error)
+                           .err(),
+                   Some(ClassifError::NonFiniteValue));
+        let b = BoundsInfo::with_weights(2,
+                                         &values,
+                                         &weights,
+                                         Classification::Quantiles,
+                                         MissingValues::Drop)
+                .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 3., 4.]);
+        assert_eq!(b.nb_missing, 1);
+        assert_eq!(b.mean, 3.);
+    }
+
     #[test]
     fn test_equal_interval_breaks() {
         let mut values = get_test_values();
         let b = BoundsInfo::new(4, &values, Classification::EqualInterval).unwrap();
         assert_eq!(b.bounds.as_slice(), [1., 3.75, 6.5, 9.25, 12.]);
         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let breaks = get_equal_interval(&values, 4);
+        let breaks = get_equal_interval(&values, 4).unwrap();
         assert_eq!(breaks.as_slice(), [1., 3.75, 6.5, 9.25, 12.]);
     }
 
@@ -118,7 +615,7 @@ mod tests {
                     8.857142857142856,
                     12.]);
         values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let breaks = get_arithmetic_breaks(&values, 6);
+        let breaks = get_arithmetic_breaks(&values, 6).unwrap();
         assert_eq!(breaks.as_slice(),
                    [1.,
                     1.5238095238095237,
@@ -129,6 +626,180 @@ mod tests {
                     12.]);
     }
 
+    #[test]
+    fn test_geometric_breaks() {
+        let mut values = get_test_values();
+        let b = BoundsInfo::new(4, &values, Classification::Geometric).unwrap();
+        assert_eq!(b.bounds.as_slice(),
+                   [1.0, 1.8612097182041991, 3.4641016151377544, 6.447419590941251, 12.0]);
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let breaks = get_geometric_breaks(&values, 4).unwrap();
+        assert_eq!(breaks.as_slice(),
+                   [1.0, 1.8612097182041991, 3.4641016151377544, 6.447419590941251, 12.0]);
+        assert_eq!(get_geometric_breaks(&[-1., 2., 3.], 2).err(),
+                   Some(ClassifError::OnlyPositive(MayFail::GeometricBreaks)));
+        // A range whose ratio overflows:
+        let breaks = get_geometric_breaks(&[1e-300, 5., 1e300], 3).unwrap();
+        assert!(breaks.iter().all(|b| b.is_finite()));
+        assert!(breaks.windows(2).all(|w| w[0] < w[1]));
+        assert_approx_eq!(breaks[1].log10(), -100., 1e-9);
+        assert_approx_eq!(breaks[2].log10(), 100., 1e-9);
+    }
+
+    #[test]
+    fn test_log_equal_interval() {
+        let values = [0., 1., 9., 99., 999., 9999.];
+        let b = BoundsInfo::new(4,
+                                &values,
+                                Classification::LogEqualInterval {
+                                    base: 10.,
+                                    log1p: true,
+                                })
+                .unwrap();
+        let expected = [0., 9., 99., 999., 9999.];
+        for (b, e) in b.bounds.iter().zip(expected.iter()) {
+            assert_approx_eq!(b, e, 1e-9);
+        }
+        let breaks = get_log_equal_interval(&[1., 10., 100., 1000.], 3, 10., false).unwrap();
+        assert_approx_eq!(breaks[1], 10., 1e-9);
+        assert_approx_eq!(breaks[2], 100., 1e-9);
+        // Zero values are only accepted when using log1p:
+        assert_eq!(get_log_equal_interval(&values, 2, 10., false).err(),
+                   Some(ClassifError::OnlyPositive(MayFail::LogEqualInterval)));
+        assert_eq!(get_log_equal_interval(&values, 2, 1., true).err(),
+                   Some(ClassifError::InvalidParameter("logarithm base")));
+    }
+
+    #[test]
+    fn test_maximum_breaks() {
+        let values = [5., 1., 2., 10., 11., 3., 20., 12.];
+        let b = BoundsInfo::new(3,
+                                &values,
+                                Classification::MaximumBreaks { placement: BreakPlacement::Midpoint })
+                .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 7.5, 16., 20.]);
+        let sorted = [1., 2., 3., 5., 10., 11., 12., 20.];
+        let breaks = get_maximum_breaks(&sorted, 3, BreakPlacement::Lower).unwrap();
+        assert_eq!(breaks.as_slice(), [1., 5., 12., 20.]);
+        // The upper values of the gaps are in the upper classes:
+        let b = BoundsInfo::new(3,
+                                &sorted,
+                                Classification::MaximumBreaks { placement: BreakPlacement::Upper })
+                .unwrap();
+        assert!(b.bounds[1] < 10. && b.bounds[2] < 20. && b.bounds[3] == 20.);
+        assert_eq!(b.class_counts(&sorted), [4, 3, 1]);
+        // Only two distinct values, so no more than two classes:
+        assert_eq!(get_maximum_breaks(&[1., 1., 2., 2.], 3, BreakPlacement::Lower).err(),
+                   Some(ClassifError::InvalidClassNumber(3)));
+    }
+
+    #[test]
+    fn test_std_dev_breaks() {
+        let mut values = get_test_values();
+        let b = BoundsInfo::new(0,
+                                &values,
+                                Classification::StandardDeviation {
+                                    width: StdDevWidth::One,
+                                    mean_centered: false,
+                                })
+                .unwrap();
+        // The mean (3.789...) is a break, the lower bound at (mean - σ) is clamped to the minimum:
+        assert_eq!(b.bounds.as_slice(),
+                   [1.0, 3.789473684210526, 6.619369486056855, 9.449265287903184, 12.0]);
+        assert_eq!(b.nb_class, 4);
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let breaks = get_std_dev_breaks(&values, StdDevWidth::One, true).unwrap();
+        assert_eq!(breaks.as_slice(),
+                   [1.0,
+                    2.374525783287362,
+                    5.204421585133691,
+                    8.03431738698002,
+                    10.864213188826348,
+                    12.0]);
+        let breaks = get_std_dev_breaks(&values, StdDevWidth::Half, false).unwrap();
+        assert_eq!(breaks.len(), 9);
+        assert_eq!(breaks[2], 3.789473684210526);
+    }
+
+    #[test]
+    fn test_pretty_breaks() {
+        // Same results as R `pretty` function:
+        let values = (1..16).map(|v| v as f64).collect::<Vec<f64>>();
+        let breaks = get_pretty_breaks(&values, 5).unwrap();
+        assert_eq!(breaks.as_slice(), [0., 2., 4., 6., 8., 10., 12., 14., 16.]);
+        let breaks = get_pretty_breaks(&[0.013, 0.2, 0.5, 0.7, 0.981], 5).unwrap();
+        assert_eq!(breaks.as_slice(), [0., 0.2, 0.4, 0.6, 0.8, 1.]);
+        let b = BoundsInfo::new(4, &get_test_values(), Classification::Pretty).unwrap();
+        assert_eq!(b.bounds.as_slice(), [0., 2., 4., 6., 8., 10., 12.]);
+        assert_eq!(b.nb_class, 6);
+        // The number of class is limited to the number of values:
+        assert_eq!(BoundsInfo::new(1000, &[1., 1.0000001], Classification::Pretty).err(),
+                   Some(ClassifError::InvalidClassNumber(1000)));
+        // A range which can't be divided:
+        assert_eq!(BoundsInfo::new(2, &[-1e308, 1e308], Classification::Pretty).err(),
+                   Some(ClassifError::InvalidParameter("range of the values")));
+    }
+
+    #[test]
+    fn test_round_bounds() {
+        let values = get_test_values();
+        let mut b = BoundsInfo::new(6, &values, Classification::Arithmetic).unwrap();
+        b.round_bounds(&values, 2);
+        assert_eq!(b.bounds.as_slice(), [1., 1.5, 2.6, 4.1, 6.2, 8.9, 12.]);
+        // The range of the values is still covered:
+        let values = [1.234, 2.5, 5.678];
+        let mut b = BoundsInfo::new(3, &values, Classification::EqualInterval).unwrap();
+        b.round_bounds(&values, 1);
+        assert_eq!(b.bounds.as_slice(), [1., 3., 4., 6.]);
+        // More digits are kept rather than making an empty class:
+        let values = [1.11, 1.12, 1.13, 1.14];
+        let mut b = BoundsInfo::new(3, &values, Classification::EqualInterval).unwrap();
+        b.round_bounds(&values, 2);
+        assert_eq!(b.bounds.as_slice(), [1.1, 1.12, 1.13, 1.2]);
+        let values = [1., 1.07, 1.5, 2.];
+        let mut b = BoundsInfo::from_breaks(&[1., 1.06, 1.14, 2.], &values, false, MissingValues::Error)
+            .unwrap();
+        b.round_bounds(&values, 2);
+        assert_eq!(b.bounds.as_slice(), [1., 1.06, 1.1, 2.]);
+        assert_eq!(b.class_counts(&values), [1, 1, 2]);
+        // Very small values:
+        let values = [1e-310, 1., 2.];
+        let mut b = BoundsInfo::new(2, &values, Classification::EqualInterval).unwrap();
+        b.round_bounds(&values, 2);
+        assert_eq!(b.bounds.as_slice(), [1e-310, 1., 2.]);
+    }
+
+    #[test]
+    fn test_manual_breaks() {
+        let values = get_test_values();
+        let b = BoundsInfo::from_breaks(&[1., 3., 5., 10., 12.], &values, false, MissingValues::Error)
+            .unwrap();
+        assert_eq!(b.type_classif, Classification::Manual);
+        assert_eq!(b.nb_class, 4);
+        assert_eq!(b.get_class_index(4.), Some(1));
+        assert_eq!(b.class_counts(&values), [48, 7, 19, 2]);
+        let b = BoundsInfo::from_breaks(&[0., 3., 5., 10.], &values, false, MissingValues::Error)
+            .unwrap();
+        assert_eq!(b.bounds.as_slice(), [0., 3., 5., 10.]);
+        assert_eq!(b.get_class_index(11.), None);
+        let b = BoundsInfo::from_breaks(&[0., 3., 5., 10.], &values, true, MissingValues::Error)
+            .unwrap();
+        assert_eq!(b.bounds.as_slice(), [0., 3., 5., 12.]);
+        assert_eq!(b.get_class_index(11.), Some(2));
+        assert_eq!(BoundsInfo::from_breaks(&[1., 5., 3.], &values, true, MissingValues::Error).err(),
+                   Some(ClassifError::InvalidParameter("breaks have to be strictly increasing")));
+        assert!(BoundsInfo::new(3, &values, Classification::Manual).is_err());
+        // Official thresholds applied on values with missing values:
+        let values = [1., f64::NAN, 4., 6.];
+        assert_eq!(BoundsInfo::from_breaks(&[0., 5., 10.], &values, false, MissingValues::Error)
+                       .err(),
+                   Some(ClassifError::NonFiniteValue));
+        let b = BoundsInfo::from_breaks(&[0., 5., 10.], &values, false, MissingValues::NoDataClass)
+            .unwrap();
+        assert_eq!(b.nb_missing, 1);
+        assert_eq!(b.classify(&values), vec![Some(0), Some(2), Some(0), Some(1)]);
+    }
+
     #[test]
     fn test_get_class_index() {
         let values = get_test_values();
@@ -146,26 +817,201 @@ mod tests {
         assert_eq!(b.get_class_index(15.0), None);
     }
 
+    #[test]
+    fn test_errors() {
+        let values = get_test_values();
+        assert_eq!(BoundsInfo::new(4, &values[..1], Classification::Quantiles).err(),
+                   Some(ClassifError::TooFewValues(1, 2)));
+        assert_eq!(BoundsInfo::new(1, &values, Classification::JenksNaturalBreaks).err(),
+                   Some(ClassifError::InvalidClassNumber(1)));
+        assert_eq!(BoundsInfo::new(77, &values, Classification::EqualInterval).err(),
+                   Some(ClassifError::InvalidClassNumber(77)));
+        assert_eq!(BoundsInfo::new(3, &[1., f64::NAN, 3., 4.], Classification::Quantiles).err(),
+                   Some(ClassifError::NonFiniteValue));
+        assert_eq!(get_head_tail_breaks(&[1., 2., f64::INFINITY]).err(),
+                   Some(ClassifError::NonFiniteValue));
+        assert_eq!(get_jenks_breaks(&[3., 3., 3., 3.], 2).err(),
+                   Some(ClassifError::DegenerateSerie));
+        assert_eq!(get_quantiles::<f64>(&[], 2).err(),
+                   Some(ClassifError::TooFewValues(0, 2)));
+        assert_eq!(stats::mean::<f64>(&[]).err(), Some(ClassifError::TooFewValues(0, 1)));
+        assert_eq!(stats::kurtosis(&[1., 2., 3.]).err(), Some(ClassifError::TooFewValues(3, 4)));
+        assert_eq!(stats::geometric_mean(&[1., -2., 3.]).err(),
+                   Some(ClassifError::OnlyPositive(MayFail::GeometricMean)));
+    }
+
+    #[test]
+    fn test_missing_values() {
+        let mut values = get_test_values().to_vec();
+        values.extend_from_slice(&[f64::NAN, f64::INFINITY, f64::NAN]);
+        assert_eq!(BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).err(),
+                   Some(ClassifError::NonFiniteValue));
+        let b = BoundsInfo::with_missing_values(5,
+                                                &values,
+                                                Classification::JenksNaturalBreaks,
+                                                MissingValues::Drop)
+                .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1.0, 2.0, 4.0, 7.0, 9.0, 12.0]);
+        assert_eq!(b.nb_missing, 3);
+        assert_eq!(b.get_class_index(f64::NAN), None);
+        let b = BoundsInfo::with_missing_values(4,
+                                                &values,
+                                                Classification::Quantiles,
+                                                MissingValues::NoDataClass)
+                .unwrap();
+        assert_eq!(b.bounds.as_slice(), [1., 2., 3., 6., 12.]);
+        assert_eq!(b.nb_missing, 3);
+        assert_eq!(b.get_class_index(f64::NAN), Some(4));
+        assert_eq!(b.get_class_index(f64::NEG_INFINITY), Some(4));
+        // The number of class is checked against the number of finite values:
+        assert_eq!(BoundsInfo::with_missing_values(3,
+                                                   &[1., f64::NAN, 2.],
+                                                   Classification::Quantiles,
+                                                   MissingValues::Drop)
+                           .err(),
+                   Some(ClassifError::InvalidClassNumber(3)));
+    }
+
+    #[test]
+    fn test_quality_metrics() {
+        let values = get_test_values();
+        let b = BoundsInfo::new(5, &values, Classification::JenksNaturalBreaks).unwrap();
+        assert_eq!(b.class_counts(&values), [34, 18, 13, 8, 3]);
+        assert_approx_eq!(b.sdcm(&values).unwrap(), 23.149007038712917);
+        assert_approx_eq!(b.gvf(&values).unwrap(), 1. - 23.149007038712917 / 608.631578947369);
+        assert_approx_eq!(b.tai(&values).unwrap(), 0.792118323790214);
+        let stats = b.class_stats(&values);
+        assert_eq!(stats[4],
+                   ClassStats {
+                       count: 3,
+                       mean: Some(11.0),
+                       variance: Some(0.6666666666666666),
+                   });
+        // Jenks natural breaks should fit better than equal intervals:
+        let b2 = BoundsInfo::new(5, &values, Classification::EqualInterval).unwrap();
+        assert!(b2.gvf(&values).unwrap() < b.gvf(&values).unwrap());
+    }
+
+    #[test]
+    fn test_select_nb_class() {
+        let values = get_test_values();
+        let s = select_nb_class(&values,
+                                Classification::JenksNaturalBreaks,
+                                2,
+                                8,
+                                NbClassCriterion::GvfThreshold(0.9))
+                .unwrap();
+        assert_eq!(s.scores.len(), 7);
+        assert_eq!(s.scores[0].nb_class, 2);
+        assert!(s.scores.windows(2).all(|w| w[0].sdcm >= w[1].sdcm));
+        assert!(s.scores[(s.bounds_info.nb_class - 2) as usize].gvf >= 0.9);
+        assert!(s.scores[(s.bounds_info.nb_class - 3) as usize].gvf < 0.9);
+        // log2(76) = 6.2 so Sturges' rule gives 8 classes:
+        let s = select_nb_class(&values,
+                                Classification::Quantiles,
+                                2,
+                                10,
+                                NbClassCriterion::Sturges)
+                .unwrap();
+        assert_eq!(s.bounds_info.nb_class, 8);
+        // ... clamped to the requested range:
+        let s = select_nb_class(&values, Classification::Quantiles, 2, 5, NbClassCriterion::Sturges)
+            .unwrap();
+        assert_eq!(s.bounds_info.nb_class, 5);
+        let s = select_nb_class(&values,
+                                Classification::EqualInterval,
+                                2,
+                                10,
+                                NbClassCriterion::Elbow)
+                .unwrap();
+        assert!(s.bounds_info.nb_class > 2 && s.bounds_info.nb_class < 10);
+        assert_eq!(select_nb_class(&values, Classification::Quantiles, 6, 4, NbClassCriterion::Scott)
+                       .err(),
+                   Some(ClassifError::InvalidClassNumber(4)));
+        // Methods ignoring the number of class are rejected:
+        assert!(select_nb_class(&values, Classification::HeadTail, 2, 8, NbClassCriterion::Elbow)
+                    .is_err());
+        // Only the powers of two are tried with the Nested Means method:
+        let s = select_nb_class(&values, Classification::NestedMeans, 3, 9, NbClassCriterion::Sturges)
+            .unwrap();
+        assert_eq!(s.scores.iter().map(|s| s.nb_class).collect::<Vec<u32>>(),
+                   vec![4, 8]);
+        assert_eq!(s.bounds_info.nb_class, 8);
+        // The requested number of class is kept along with the computed one:
+        let s = select_nb_class(&values, Classification::Pretty, 2, 8, NbClassCriterion::Elbow)
+            .unwrap();
+        assert_eq!(s.scores.iter().map(|s| s.nb_class).collect::<Vec<u32>>(),
+                   (2..9).collect::<Vec<u32>>());
+        assert!(s.scores
+                    .iter()
+                    .any(|s| s.actual_nb_class != s.nb_class));
+        let nb_class = s.bounds_info.nb_class;
+        assert!(s.scores
+                    .iter()
+                    .any(|s| s.actual_nb_class == nb_class));
+        // The number of class is limited to the number of distinct values:
+        let s = select_nb_class(&[1., 1., 1., 1., 1., 1., 1., 8., 9., 10.],
+                                Classification::MaximumBreaks { placement: BreakPlacement::Midpoint },
+                                2,
+                                9,
+                                NbClassCriterion::Elbow)
+                .unwrap();
+        assert_eq!(s.scores.len(), 3);
+    }
+
     #[test]
     fn test_kurtosis() {
         let values = get_test_values();
-        let kv = stats::kurtosis(&values);
+        let kv = stats::kurtosis(&values).unwrap();
         assert_eq!(kv, 0.042107329018970074);
     }
 
     #[test]
     fn test_variance() {
         let values = get_test_values();
-        let r = stats::sum_pow_deviations(&values, 2);
+        let r = stats::sum_pow_deviations(&values, 2).unwrap();
         assert_eq!(r, 608.631578947369);
-        let v = stats::variance(&values);
+        let v = stats::variance(&values).unwrap();
         assert_eq!(v, 8.008310249307486);
     }
 
+    #[test]
+    fn test_online_stats() {
+        let values = get_test_values();
+        let mut online = stats::OnlineStats::new();
+        assert_eq!(online.mean().err(), Some(ClassifError::TooFewValues(0, 1)));
+        online.extend(&values).unwrap();
+        assert_eq!(online.count(), values.len());
+        assert_approx_eq!(online.mean().unwrap(), stats::mean(&values).unwrap());
+        assert_approx_eq!(online.variance().unwrap(), stats::variance(&values).unwrap());
+        assert_approx_eq!(online.skewness().unwrap(), stats::skewness(&values).unwrap());
+        assert_approx_eq!(online.kurtosis().unwrap(), stats::kurtosis(&values).unwrap());
+        assert_approx_eq!(online.rootmeansquare().unwrap(),
+                          stats::rootmeansquare(&values).unwrap());
+        assert_eq!(online.min().unwrap(), 1.);
+        assert_eq!(online.max().unwrap(), 12.);
+        // Merging partial results gives the same statistics:
+        let mut merged = stats::OnlineStats::new();
+        for chunk in values.chunks(10) {
+            let mut partial = stats::OnlineStats::new();
+            partial.extend(chunk).unwrap();
+            merged.merge(&partial);
+        }
+        assert_eq!(merged.count(), online.count());
+        assert_approx_eq!(merged.mean().unwrap(), online.mean().unwrap());
+        assert_approx_eq!(merged.variance().unwrap(), online.variance().unwrap());
+        assert_approx_eq!(merged.skewness().unwrap(), online.skewness().unwrap());
+        assert_approx_eq!(merged.kurtosis().unwrap(), online.kurtosis().unwrap());
+        assert_eq!(online.push(f64::NAN).err(), Some(ClassifError::NonFiniteValue));
+        assert_eq!(online.count(), values.len());
+        // Known value (same as Excel's SKEW function):
+        assert_approx_eq!(stats::skewness(&[1., 2., 3., 10.]).unwrap(), 1.763632614803888);
+    }
+
     #[test]
     fn test_root_mean_square() {
         let values = [-1., 1., -1., 1.];
-        let v = stats::rootmeansquare(&values);
+        let v = stats::rootmeansquare(&values).unwrap();
         assert_eq!(v, 1.);
     }
 
@@ -173,11 +1019,11 @@ mod tests {
     fn test_median() {
         // Number of values is odd:
         let values = [1., 3., 3., 6., 7., 8., 9.];
-        let median = stats::median(&values);
+        let median = stats::median(&values).unwrap();
         assert_eq!(median, 6.);
         // Number of values is even:
         let values = [1., 2., 3., 4., 5., 6., 8., 9.];
-        let median = stats::median(&values);
+        let median = stats::median(&values).unwrap();
         assert_eq!(median, 4.5);
     }
 
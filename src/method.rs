@@ -0,0 +1,171 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::{BoundsInfo, Classification, IntervalClosure, MissingValues, get_arithmetic_breaks,
+              get_equal_interval, get_geometric_breaks, get_head_tail_breaks,
+              get_head_tail_ratio_breaks, get_log_equal_interval, get_maximum_breaks,
+              get_nested_means_breaks, get_quantiles, get_std_dev_breaks, get_tail_head_breaks};
+use error::{ClassifError, ClassifResult, check_classif_input};
+use jenks::{get_jenks_breaks, get_kmeans_clusters};
+use pretty::get_pretty_breaks;
+use stats::mean;
+
+/// A method computing the breaks of a classification, allowing to use
+/// classification methods which are not provided by this library.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{BoundsInfo, BreaksMethod, ClassifResult, Classification, MissingValues};
+/// # fn main() {
+/// // Breaks at the midrange of the values:
+/// struct Midrange;
+///
+/// impl BreaksMethod<f64> for Midrange {
+///     fn name(&self) -> String {
+///         "midrange".to_string()
+///     }
+///
+///     fn breaks(&self, sorted_values: &[f64], _nb_class: u32) -> ClassifResult<Vec<f64>> {
+///         let (min, max) = (sorted_values[0], sorted_values[sorted_values.len() - 1]);
+///         Ok(vec![min, (min + max) / 2., max])
+///     }
+/// }
+///
+/// let values = [1., 2., 3., 4., 9.];
+/// let bounds_info = BoundsInfo::from_method(&Midrange, 2, &values, MissingValues::Error).unwrap();
+/// assert_eq!(bounds_info.bounds, vec![1., 5., 9.]);
+/// assert_eq!(bounds_info.type_classif, Classification::Custom { name: "midrange".to_string() });
+/// # }
+/// ```
+pub trait BreaksMethod<T> {
+    /// The name of the method, recorded in the `type_classif` field of the computed `BoundsInfo`.
+    fn name(&self) -> String;
+
+    /// Compute the breaks (ie. the minimum, the upper bound of each class but the last one
+    /// and the maximum) of `nb_class` classes on a list of sorted values.
+    fn breaks(&self, sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>;
+
+    /// The `Classification` recorded in the computed `BoundsInfo`
+    /// (a `Classification::Custom` named after the method by default).
+    fn classification(&self) -> Classification {
+        Classification::Custom { name: self.name() }
+    }
+
+    // Compute the whole `BoundsInfo` instead of only the breaks, which is
+    // only done by the built-in methods (which may compute the cluster centers).
+    #[doc(hidden)]
+    fn bounds_info(&self,
+                   _nb_class: u32,
+                   _values: &[T],
+                   _missing_values: MissingValues)
+                   -> Option<ClassifResult<BoundsInfo<T>>> {
+        None
+    }
+}
+
+impl<T> BreaksMethod<T> for Classification
+    where T: Float + NumAssignOps
+{
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    fn breaks(&self, sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>> {
+        match *self {
+            Classification::JenksNaturalBreaks => get_jenks_breaks(sorted_values, nb_class),
+            Classification::KMeans => {
+                get_kmeans_clusters(sorted_values, nb_class).map(|clusters| clusters.breaks)
+            }
+            Classification::Quantiles => get_quantiles(sorted_values, nb_class),
+            Classification::EqualInterval => get_equal_interval(sorted_values, nb_class),
+            Classification::HeadTail => get_head_tail_breaks(sorted_values),
+            Classification::HeadTailRatio { head_ratio, max_class } => {
+                get_head_tail_ratio_breaks(sorted_values, head_ratio, max_class)
+            }
+            Classification::TailHead => get_tail_head_breaks(sorted_values),
+            Classification::NestedMeans => {
+                if !nb_class.is_power_of_two() {
+                    return Err(ClassifError::InvalidClassNumber(nb_class));
+                }
+                get_nested_means_breaks(sorted_values, nb_class.trailing_zeros())
+            }
+            Classification::Arithmetic => get_arithmetic_breaks(sorted_values, nb_class),
+            Classification::Geometric => get_geometric_breaks(sorted_values, nb_class),
+            Classification::Pretty => get_pretty_breaks(sorted_values, nb_class),
+            Classification::Manual => {
+                Err(ClassifError::InvalidParameter("manual breaks have to be provided \
+                                                    using BoundsInfo::from_breaks"))
+            }
+            Classification::LogEqualInterval { base, log1p } => {
+                get_log_equal_interval(sorted_values, nb_class, base, log1p)
+            }
+            Classification::MaximumBreaks { placement } => {
+                get_maximum_breaks(sorted_values, nb_class, placement)
+            }
+            Classification::StandardDeviation { width, mean_centered } => {
+                get_std_dev_breaks(sorted_values, width, mean_centered)
+            }
+            Classification::Custom { .. } => {
+                Err(ClassifError::InvalidParameter("custom methods have to be used \
+                                                    with BoundsInfo::from_method"))
+            }
+        }
+    }
+
+    fn classification(&self) -> Classification {
+        self.clone()
+    }
+
+    fn bounds_info(&self,
+                   nb_class: u32,
+                   values: &[T],
+                   missing_values: MissingValues)
+                   -> Option<ClassifResult<BoundsInfo<T>>> {
+        Some(BoundsInfo::with_missing_values(nb_class, values, self.clone(), missing_values))
+    }
+}
+
+impl<T> BoundsInfo<T>
+    where T: Float + NumAssignOps
+{
+    /// Compute the bounds of `nb_class` classes on `values` (which don't need to be sorted)
+    /// according to any method implementing the [`BreaksMethod`] trait.
+    ///
+    /// The breaks returned by a custom method have to be finite and sorted; the meaning
+    /// of `nb_class` is up to the method (the number of class of the result being
+    /// deduced from the breaks). The missing values (NaN or infinite values) of `values`
+    /// are handled according to `missing_values`.
+    ///
+    /// [`BreaksMethod`]: trait.BreaksMethod.html
+    pub fn from_method<M>(method: &M,
+                          nb_class: u32,
+                          values: &[T],
+                          missing_values: MissingValues)
+                          -> ClassifResult<Self>
+        where M: BreaksMethod<T> + ?Sized
+    {
+        if let Some(bounds_info) = method.bounds_info(nb_class, values, missing_values) {
+            return bounds_info;
+        }
+        let (mut v, nb_missing) = missing_values.filter(values)?;
+        check_classif_input(&v, None)?;
+        v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let breaks = method.breaks(&v, nb_class)?;
+        if breaks.len() < 2 || breaks.iter().any(|b| !b.is_finite()) ||
+           breaks.windows(2).any(|w| w[0] > w[1]) {
+            return Err(ClassifError::InvalidParameter("the breaks have to be finite and sorted"));
+        }
+        Ok(BoundsInfo {
+               type_classif: method.classification(),
+               nb_class: (breaks.len() - 1) as u32,
+               bounds: breaks,
+               min: v[0],
+               max: v[v.len() - 1],
+               mean: mean(&v)?,
+               ht_index: None,
+               centers: None,
+               missing_values,
+               nb_missing,
+               closure: IntervalClosure::default(),
+           })
+    }
+}
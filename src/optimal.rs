@@ -0,0 +1,198 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::{BoundsInfo, Classification};
+use error::{ClassifError, ClassifResult, check_classif_input};
+use stats::{standard_deviation, sorted_quantile, QuantileMethod};
+
+/// The criterion used by [`select_nb_class`] to choose the number of class.
+///
+/// [`select_nb_class`]: fn.select_nb_class.html
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NbClassCriterion<T> {
+    /// The smallest number of class whose Goodness of Variance Fit
+    /// reaches the given threshold (such as 0.8).
+    GvfThreshold(T),
+    /// The "elbow" of the curve of the sum of squared deviations from the class means,
+    /// ie. the point of this curve the most distant from the line joining its ends.
+    Elbow,
+    /// Sturges' rule: `ceil(log2(n)) + 1`.
+    Sturges,
+    /// Scott's rule, based on a class width of `3.49 * σ * n^(-1/3)`.
+    Scott,
+    /// Freedman–Diaconis' rule, based on a class width of `2 * IQR * n^(-1/3)`.
+    FreedmanDiaconis,
+}
+
+/// The quality of the classification computed with a given number of class.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NbClassScore<T> {
+    /// The requested number of class.
+    pub nb_class: u32,
+    /// The number of class actually computed, which may differ from the requested
+    /// one for the methods where it is only indicative (ie. the `Pretty` method).
+    pub actual_nb_class: u32,
+    /// The Goodness of Variance Fit of the classification.
+    pub gvf: T,
+    /// The sum of squared deviations from the class means of the classification.
+    pub sdcm: T,
+}
+
+/// The result of [`select_nb_class`]: the chosen classification
+/// and the scores of all the tried numbers of class.
+///
+/// [`select_nb_class`]: fn.select_nb_class.html
+#[derive(Debug)]
+pub struct NbClassSelection<T> {
+    pub bounds_info: BoundsInfo<T>,
+    pub scores: Vec<NbClassScore<T>>,
+}
+
+// Number of class obtained by dividing the range of the values by `width`.
+fn nb_class_from_width<T: Float>(sorted_values: &[T], width: T) -> u32 {
+    let range = sorted_values[sorted_values.len() - 1] - sorted_values[0];
+    if width > T::zero() {
+        (range / width).ceil().to_u32().unwrap_or(u32::MAX)
+    } else {
+        u32::MAX
+    }
+}
+
+// Index of the point of the curve the most distant from the line joining its ends.
+fn elbow_index<T: Float>(curve: &[T]) -> usize {
+    let n = curve.len();
+    if n < 3 {
+        return 0;
+    }
+    let (first, last) = (curve[0], curve[n - 1]);
+    let step = (last - first) / T::from(n - 1).unwrap();
+    let mut best = 0;
+    let mut best_dist = T::zero();
+    for (i, v) in curve.iter().enumerate() {
+        // Vertical distance, proportional to the orthogonal distance to the line:
+        let dist = (first + step * T::from(i).unwrap() - *v).abs();
+        if dist > best_dist {
+            best_dist = dist;
+            best = i;
+        }
+    }
+    best
+}
+
+/// Classify `values` according to the `type_classif` method with each number of class
+/// between `min_class` and `max_class` and choose one of them according to `criterion`.
+///
+/// `max_class` is limited to the number of distinct values.
+/// Only the powers of two of this range are tried with the `NestedMeans` method,
+/// and the methods which don't use the number of class (Head-Tail, Tail-Head
+/// and Standard Deviation methods) are rejected. The number of class given by
+/// Sturges, Scott and Freedman–Diaconis rules is replaced by the closest tried one.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{select_nb_class, Classification, NbClassCriterion};
+/// # fn main() {
+/// let values = [1., 1.2, 1.4, 5.1, 5.2, 5.3, 9.9, 10.1, 10.2, 20.];
+/// let selection = select_nb_class(&values,
+///                                 Classification::JenksNaturalBreaks,
+///                                 2,
+///                                 6,
+///                                 NbClassCriterion::GvfThreshold(0.95)).unwrap();
+/// assert_eq!(selection.bounds_info.nb_class, 4);
+/// assert_eq!(selection.scores.len(), 5);
+/// # }
+/// ```
+pub fn select_nb_class<T>(values: &[T],
+                          type_classif: Classification,
+                          min_class: u32,
+                          max_class: u32,
+                          criterion: NbClassCriterion<T>)
+                          -> ClassifResult<NbClassSelection<T>>
+    where T: Float + NumAssignOps
+{
+    match type_classif {
+        Classification::HeadTail |
+        Classification::HeadTailRatio { .. } |
+        Classification::TailHead |
+        Classification::StandardDeviation { .. } => {
+            return Err(ClassifError::InvalidParameter("this classification method doesn't use \
+                                                       the number of class"))
+        }
+        _ => (),
+    }
+    check_classif_input(values, Some(min_class))?;
+    let mut sorted_values = values.to_vec();
+    sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    // There can't be more (non-empty) classes than distinct values:
+    let nb_distinct = 1 + sorted_values.windows(2).filter(|w| w[0] < w[1]).count();
+    let max_class = if max_class as usize > nb_distinct {
+        nb_distinct as u32
+    } else {
+        max_class
+    };
+    if max_class < min_class {
+        return Err(ClassifError::InvalidClassNumber(max_class));
+    }
+    let nb_classes = (min_class..max_class + 1)
+        .filter(|n| type_classif != Classification::NestedMeans || n.is_power_of_two())
+        .collect::<Vec<u32>>();
+    if nb_classes.is_empty() {
+        return Err(ClassifError::InvalidClassNumber(max_class));
+    }
+
+    let mut classifications = Vec::with_capacity(nb_classes.len());
+    let mut scores = Vec::with_capacity(nb_classes.len());
+    for &nb_class in &nb_classes {
+        let bounds_info = BoundsInfo::new(nb_class, &sorted_values, type_classif.clone())?;
+        scores.push(NbClassScore {
+                        nb_class,
+                        actual_nb_class: bounds_info.nb_class,
+                        gvf: bounds_info.gvf(&sorted_values)?,
+                        sdcm: bounds_info.sdcm(&sorted_values)?,
+                    });
+        classifications.push(bounds_info);
+    }
+
+    let n = sorted_values.len() as f64;
+    // The rules give a number of class, the other criteria the index of a score:
+    let chosen_nb_class = match criterion {
+        NbClassCriterion::GvfThreshold(threshold) => {
+            let ix = scores
+                .iter()
+                .position(|s| s.gvf >= threshold)
+                .unwrap_or(scores.len() - 1);
+            return Ok(selection(classifications, scores, ix));
+        }
+        NbClassCriterion::Elbow => {
+            let curve = scores.iter().map(|s| s.sdcm).collect::<Vec<T>>();
+            let ix = elbow_index(&curve);
+            return Ok(selection(classifications, scores, ix));
+        }
+        NbClassCriterion::Sturges => n.log2().ceil() as u32 + 1,
+        NbClassCriterion::Scott => {
+            let width = T::from(3.49 * n.powf(-1. / 3.)).unwrap() *
+                        standard_deviation(&sorted_values)?;
+            nb_class_from_width(&sorted_values, width)
+        }
+        NbClassCriterion::FreedmanDiaconis => {
+            let iqr = sorted_quantile(&sorted_values, 0.75, QuantileMethod::Type7) -
+                      sorted_quantile(&sorted_values, 0.25, QuantileMethod::Type7);
+            let width = T::from(2. * n.powf(-1. / 3.)).unwrap() * iqr;
+            nb_class_from_width(&sorted_values, width)
+        }
+    };
+    // The closest number of class among the tried ones:
+    let ix = (0..nb_classes.len())
+        .min_by_key(|&i| (nb_classes[i] as i64 - chosen_nb_class as i64).abs())
+        .unwrap();
+    Ok(selection(classifications, scores, ix))
+}
+
+fn selection<T>(mut classifications: Vec<BoundsInfo<T>>,
+                scores: Vec<NbClassScore<T>>,
+                ix: usize)
+                -> NbClassSelection<T> {
+    NbClassSelection {
+        bounds_info: classifications.swap_remove(ix),
+        scores,
+    }
+}
@@ -0,0 +1,204 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::{BoundsInfo, IntervalClosure};
+use error::{ClassifError, ClassifResult, check_classif_input};
+
+// Value of `i * mult * 10^exponent`, computed so as to avoid
+// artifacts such as `0.30000000000000004` for negative exponents.
+fn nice_number(i: f64, mult: f64, exponent: i32) -> f64 {
+    if exponent >= 0 {
+        i * mult * 10f64.powi(exponent)
+    } else {
+        i * mult / 10f64.powi(-exponent)
+    }
+}
+
+/// Compute "pretty" breaks on a list of sorted values, ie. round values
+/// (1, 2 or 5 times a power of 10) equally spaced and covering the range
+/// of the values, as computed by the `pretty` function of R.
+///
+/// `nb_class` is the desired number of class (between 1 and the number of values):
+/// the actual number of class may be slightly different and the first and last bounds
+/// may lie outside the range of the values.
+///
+/// Returns an error if the range of the values is too large (or too small)
+/// to be divided in round values.
+pub fn get_pretty_breaks<T>(sorted_values: &[T], nb_class: u32) -> ClassifResult<Vec<T>>
+    where T: Float + NumAssignOps
+{
+    check_classif_input(sorted_values, None)?;
+    if nb_class < 1 || nb_class as usize > sorted_values.len() {
+        return Err(ClassifError::InvalidClassNumber(nb_class));
+    }
+    // Constants used by R ("high.u.bias" and "u5.bias"):
+    let h = 1.5;
+    let h5 = 0.5 + 1.5 * h;
+    let min_n = (nb_class / 3) as f64;
+    let lo = sorted_values[0].to_f64().unwrap();
+    let up = sorted_values[sorted_values.len() - 1].to_f64().unwrap();
+    let ndiv = nb_class as f64;
+
+    let cell = (up - lo) / ndiv;
+    if !(cell.is_finite() && cell > 0.) {
+        return Err(ClassifError::InvalidParameter("range of the values"));
+    }
+    let exponent = cell.log10().floor() as i32;
+    let base = 10f64.powi(exponent);
+    let mut mult = 1.;
+    if 2. * base - cell < h * (cell - base) {
+        mult = 2.;
+        if 5. * base - cell < h5 * (cell - 2. * base) {
+            mult = 5.;
+            if 10. * base - cell < h * (cell - 5. * base) {
+                mult = 10.;
+            }
+        }
+    }
+    let unit = mult * base;
+    let mut ns = (lo / unit + 1e-7).floor();
+    let mut nu = (up / unit - 1e-7).ceil();
+    while ns * unit > lo + 1e-10 * unit {
+        ns -= 1.;
+    }
+    while nu * unit < up - 1e-10 * unit {
+        nu += 1.;
+    }
+    let k = nu - ns;
+    if k < min_n {
+        let missing = min_n - k;
+        let half = (missing / 2.).floor();
+        if ns >= 0. {
+            nu += half;
+            ns -= missing - half;
+        } else {
+            ns -= half;
+            nu += missing - half;
+        }
+    }
+    let nb_breaks = (nu - ns) as usize + 1;
+    let breaks = (0..nb_breaks)
+        .map(|i| T::from(nice_number(ns + i as f64, mult, exponent)).unwrap())
+        .collect::<Vec<T>>();
+    if breaks.len() < 2 || breaks.iter().any(|b| !b.is_finite()) ||
+       breaks.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(ClassifError::InvalidParameter("range of the values"));
+    }
+    Ok(breaks)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Rounding {
+    Nearest,
+    Down,
+    Up,
+}
+
+// Value of `v * 10^exponent`, applying large exponents in two steps
+// as `10^exponent` overflows beyond 308 (ie. for very small values).
+fn scale(v: f64, exponent: i32) -> f64 {
+    if exponent.abs() > 300 {
+        scale(scale(v, exponent / 2), exponent - exponent / 2)
+    } else if exponent >= 0 {
+        v * 10f64.powi(exponent)
+    } else {
+        v / 10f64.powi(-exponent)
+    }
+}
+
+// Round `value` to `digits` significant digits.
+fn round_significant<T: Float>(value: T, digits: u32, rounding: Rounding) -> T {
+    let v = value.to_f64().unwrap();
+    if v == 0. || !v.is_finite() {
+        return value;
+    }
+    let exponent = digits as i32 - 1 - v.abs().log10().floor() as i32;
+    let scaled = scale(v, exponent);
+    // Don't move a value which is already round to the next number
+    // because of a floating point artifact:
+    let nearest = scaled.round();
+    let rounded = if (scaled - nearest).abs() < 1e-9 {
+        nearest
+    } else {
+        match rounding {
+            Rounding::Nearest => nearest,
+            Rounding::Down => scaled.floor(),
+            Rounding::Up => scaled.ceil(),
+        }
+    };
+    T::from(scale(rounded, -exponent)).unwrap()
+}
+
+impl<T> BoundsInfo<T>
+    where T: Float + NumAssignOps
+{
+    /// Round the bounds to `significant_digits` significant digits, to be used in legends.
+    ///
+    /// The first bound is rounded down and the last one is rounded up so that they still
+    /// cover the minimum and the maximum. When rounding a bound would make it equal to
+    /// the previous one or would empty a class containing some of `values` (the values
+    /// which were classified), more significant digits are kept for this bound.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification};
+    /// # fn main() {
+    /// let values = [1.02, 1.3, 2.4, 5.0, 2.1, 5.3, 4.0, 3.0, 1.3, 4.3, 6.07, 2.1];
+    /// let mut bounds_info = BoundsInfo::new(3, &values, Classification::EqualInterval).unwrap();
+    /// bounds_info.round_bounds(&values, 2);
+    /// assert_eq!(bounds_info.bounds, vec![1.0, 2.7, 4.4, 6.1]);
+    /// # }
+    /// ```
+    pub fn round_bounds(&mut self, values: &[T], significant_digits: u32) {
+        let significant_digits = if significant_digits == 0 { 1 } else { significant_digits };
+        let mut sorted_values = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .cloned()
+            .collect::<Vec<T>>();
+        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let was_empty = (0..self.bounds.len() - 1)
+            .map(|j| self.is_empty_class(&sorted_values, j))
+            .collect::<Vec<bool>>();
+        let last = self.bounds.len() - 1;
+        for i in 0..self.bounds.len() {
+            let value = self.bounds[i];
+            let rounding = if i == 0 {
+                Rounding::Down
+            } else if i == last {
+                Rounding::Up
+            } else {
+                Rounding::Nearest
+            };
+            let mut digits = significant_digits;
+            loop {
+                self.bounds[i] = round_significant(value, digits, rounding);
+                let merged = i > 0 && self.bounds[i] <= self.bounds[i - 1] &&
+                             self.bounds[i - 1] < value;
+                let emptied = (i > 0 && !was_empty[i - 1] &&
+                               self.is_empty_class(&sorted_values, i - 1)) ||
+                              (i < last && !was_empty[i] &&
+                               self.is_empty_class(&sorted_values, i));
+                if !(merged || emptied) || digits >= 17 {
+                    break;
+                }
+                digits += 1;
+            }
+        }
+    }
+
+    // Whether none of the sorted values belong to the class `j`.
+    fn is_empty_class(&self, sorted_values: &[T], j: usize) -> bool {
+        let (lower, upper) = (self.bounds[j], self.bounds[j + 1]);
+        let is_last = j + 2 == self.bounds.len();
+        // The first value which is not below the class:
+        let start = match self.closure {
+            IntervalClosure::Right if j > 0 => sorted_values.partition_point(|v| *v <= lower),
+            _ => sorted_values.partition_point(|v| *v < lower),
+        };
+        match sorted_values.get(start) {
+            None => true,
+            Some(v) if self.closure == IntervalClosure::Left && !is_last => *v >= upper,
+            Some(v) => *v > upper,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::BoundsInfo;
+use error::{ClassifError, ClassifResult};
+use stats::{mean, sum_pow_deviations, variance};
+
+/// Basic statistical informations about the values belonging to a class.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClassStats<T> {
+    /// The number of values in the class.
+    pub count: usize,
+    /// The mean of the values in the class (None if the class is empty).
+    pub mean: Option<T>,
+    /// The variance of the values in the class (None if the class is empty).
+    pub variance: Option<T>,
+}
+
+/// Measures of the quality of a classification, allowing to compare the bounds
+/// computed with the various methods on the same values.
+///
+/// They are computed on the values falling in one of the regular classes
+/// (values out of range and missing values are ignored).
+impl<T> BoundsInfo<T>
+    where T: Float + NumAssignOps
+{
+    // Split `values` according to the class they belong to.
+    fn group_values(&self, values: &[T]) -> Vec<Vec<T>> {
+        let mut groups = vec![Vec::new(); self.nb_class as usize];
+        for &v in values {
+            if let Some(ix) = self.get_class_index(v) {
+                if ix < self.nb_class {
+                    groups[ix as usize].push(v);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Returns the number of values, their mean and their variance for each class.
+    pub fn class_stats(&self, values: &[T]) -> Vec<ClassStats<T>> {
+        self.group_values(values)
+            .iter()
+            .map(|g| {
+                     ClassStats {
+                         count: g.len(),
+                         mean: mean(g).ok(),
+                         variance: variance(g).ok(),
+                     }
+                 })
+            .collect()
+    }
+
+    /// Returns the number of values in each class.
+    pub fn class_counts(&self, values: &[T]) -> Vec<usize> {
+        self.group_values(values).iter().map(|g| g.len()).collect()
+    }
+
+    /// Compute the sum of squared deviations from the class means (SDCM).
+    pub fn sdcm(&self, values: &[T]) -> ClassifResult<T> {
+        let mut sum = T::zero();
+        for g in self.group_values(values).iter().filter(|g| !g.is_empty()) {
+            sum += sum_pow_deviations(g, 2)?;
+        }
+        Ok(sum)
+    }
+
+    /// Compute the Goodness of Variance Fit (GVF), ranging from 0 (no fit)
+    /// to 1 (perfect fit), ie. `(SDAM - SDCM) / SDAM` where SDAM is the sum of
+    /// squared deviations from the mean of all the values.
+    ///
+    /// ```rust
+    /// # extern crate classif;
+    /// # use classif::{BoundsInfo, Classification};
+    /// # fn main() {
+    /// let values = [1., 1.2, 1.4, 5.1, 5.2, 9.9, 10.1];
+    /// let bounds_info = BoundsInfo::new(3, &values, Classification::JenksNaturalBreaks).unwrap();
+    /// assert!(bounds_info.gvf(&values).unwrap() > 0.99);
+    /// # }
+    /// ```
+    pub fn gvf(&self, values: &[T]) -> ClassifResult<T> {
+        let classified = self.group_values(values).concat();
+        let sdam = sum_pow_deviations(&classified, 2)?;
+        if sdam == T::zero() {
+            return Err(ClassifError::DegenerateSerie);
+        }
+        Ok((sdam - self.sdcm(&classified)?) / sdam)
+    }
+
+    /// Compute the Tabular Accuracy Index (TAI), ranging from 0 (no fit)
+    /// to 1 (perfect fit), ie. the GVF counterpart based on absolute deviations.
+    pub fn tai(&self, values: &[T]) -> ClassifResult<T> {
+        let groups = self.group_values(values);
+        let classified = groups.concat();
+        let global_mean = mean(&classified)?;
+        let sum_abs_dev_mean = classified
+            .iter()
+            .fold(T::zero(), |s, v| s + (*v - global_mean).abs());
+        if sum_abs_dev_mean == T::zero() {
+            return Err(ClassifError::DegenerateSerie);
+        }
+        let mut sum_abs_dev_class = T::zero();
+        for g in groups.iter().filter(|g| !g.is_empty()) {
+            let class_mean = mean(g)?;
+            sum_abs_dev_class += g.iter().fold(T::zero(), |s, v| s + (*v - class_mean).abs());
+        }
+        Ok(T::one() - sum_abs_dev_class / sum_abs_dev_mean)
+    }
+}
@@ -0,0 +1,294 @@
+use num_traits::{Float, NumAssignOps};
+
+use classif::{BoundsInfo, Classification, IntervalClosure, MissingValues};
+use error::{ClassifError, ClassifResult};
+use stats::OnlineStats;
+
+// Ratio between the capacities of two consecutive compactors.
+const CAPACITY_RATIO: f64 = 2. / 3.;
+
+/// A mergeable sketch of a (possibly huge) stream of values, allowing to compute
+/// approximate quantiles (and thus Quantiles breaks) without keeping all the values
+/// in memory, nor sorting them.
+///
+/// This is an implementation of the KLL sketch (Karnin, Lang and Liberty, 2016): values
+/// are stored in a hierarchy of compactors, each of them keeping one value out of two
+/// (with a random offset) when full and passing them to the next one, where each value
+/// accounts for twice as many original values. The size of the sketch thus grows with
+/// `k` and only logarithmically with the number of values.
+///
+/// ## Error bound
+///
+/// The accuracy is set by the `k` parameter (200 by default). The rank of the value
+/// returned for the `p` quantile differs from the exact rank `p * n` by at most
+/// `ε * n` with high probability (99%), `ε` being given by [`normalized_rank_error`]:
+/// about `2.3 / k^0.97`, ie. 1.3% of the number of values when `k` is 200
+/// (and 0.3% when `k` is 1000).
+/// Until the first compaction (ie. for less than about `k` values), all the values are
+/// kept and the results are exact. The minimum, the maximum, the mean and the number
+/// of values are always exact.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::{BoundsInfo, QuantileSketch};
+/// # fn main() {
+/// let mut sketch = QuantileSketch::new();
+/// for i in 0..100000 {
+///     sketch.push((i % 1000) as f64).unwrap();
+/// }
+/// // The median is about 500 (with an error of about 1.3% of the values, ie. 13):
+/// let median = sketch.quantile(0.5).unwrap();
+/// assert!((median - 500.).abs() <= 13.);
+/// let bounds_info = BoundsInfo::from_sketch(&sketch, 4).unwrap();
+/// assert_eq!(bounds_info.bounds.len(), 5);
+/// assert_eq!(bounds_info.max, 999.);
+/// # }
+/// ```
+/// [`normalized_rank_error`]: struct.QuantileSketch.html#method.normalized_rank_error
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuantileSketch<T> {
+    k: u32,
+    // The values kept at each level, a value at level `h` accounting for `2^h` values:
+    compactors: Vec<Vec<T>>,
+    size: usize,
+    max_size: usize,
+    // State of the xorshift generator choosing the values kept by the compactions:
+    rng_state: u64,
+    stats: OnlineStats<T>,
+}
+
+impl<T> Default for QuantileSketch<T>
+    where T: Float + NumAssignOps
+{
+    fn default() -> Self {
+        QuantileSketch::new()
+    }
+}
+
+impl<T> QuantileSketch<T>
+    where T: Float + NumAssignOps
+{
+    /// Create an empty sketch with the default accuracy (`k` = 200).
+    pub fn new() -> Self {
+        QuantileSketch::with_k(200).unwrap()
+    }
+
+    /// Create an empty sketch with the given accuracy parameter (at least 8):
+    /// a larger `k` gives more accurate results but a larger sketch.
+    pub fn with_k(k: u32) -> ClassifResult<Self> {
+        if k < 8 {
+            return Err(ClassifError::InvalidParameter("k"));
+        }
+        let mut sketch = QuantileSketch {
+            k,
+            compactors: Vec::new(),
+            size: 0,
+            max_size: 0,
+            rng_state: 0x9E37_79B9_7F4A_7C15,
+            stats: OnlineStats::new(),
+        };
+        sketch.grow();
+        Ok(sketch)
+    }
+
+    /// Returns the estimated maximum error on the rank of the computed quantiles,
+    /// as a fraction of the number of values (at a 99% confidence level).
+    pub fn normalized_rank_error(&self) -> f64 {
+        2.296 / (self.k as f64).powf(0.9723)
+    }
+
+    // Number of values the compactor at level `h` can hold before being compacted.
+    fn capacity(&self, h: usize) -> usize {
+        let depth = (self.compactors.len() - h - 1) as i32;
+        (CAPACITY_RATIO.powi(depth) * self.k as f64).ceil() as usize + 1
+    }
+
+    fn grow(&mut self) {
+        self.compactors.push(Vec::new());
+        self.max_size = (0..self.compactors.len()).map(|h| self.capacity(h)).sum();
+    }
+
+    fn random_bit(&mut self) -> usize {
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+        (self.rng_state >> 32) as usize & 1
+    }
+
+    // Compact the first compactor which is full, promoting half of its values.
+    fn compress(&mut self) {
+        for h in 0..self.compactors.len() {
+            if self.compactors[h].len() >= self.capacity(h) {
+                if h + 1 >= self.compactors.len() {
+                    self.grow();
+                }
+                let offset = self.random_bit();
+                let mut values = ::std::mem::take(&mut self.compactors[h]);
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                // When the number of values is odd, the smallest one stays at this level:
+                let start = values.len() % 2;
+                let promoted = (start..values.len())
+                    .step_by(2)
+                    .map(|i| values[i + offset])
+                    .collect::<Vec<T>>();
+                values.truncate(start);
+                self.compactors[h] = values;
+                self.compactors[h + 1].extend(promoted);
+                self.size = self.compactors.iter().map(|c| c.len()).sum();
+                return;
+            }
+        }
+    }
+
+    /// Add a value, failing (without adding it) if it is NaN or infinite.
+    pub fn push(&mut self, value: T) -> ClassifResult<()> {
+        self.stats.push(value)?;
+        self.compactors[0].push(value);
+        self.size += 1;
+        if self.size >= self.max_size {
+            self.compress();
+        }
+        Ok(())
+    }
+
+    /// Add a chunk of values, failing (without adding any of them)
+    /// if they contain NaN or infinite values.
+    pub fn extend(&mut self, values: &[T]) -> ClassifResult<()> {
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        for &v in values {
+            self.push(v)?;
+        }
+        Ok(())
+    }
+
+    /// Combine the values of `other` into this sketch
+    /// (the accuracy of the result being the one of the less accurate sketch).
+    pub fn merge(&mut self, other: &QuantileSketch<T>) {
+        self.k = self.k.min(other.k);
+        while self.compactors.len() < other.compactors.len() {
+            self.grow();
+        }
+        for (h, values) in other.compactors.iter().enumerate() {
+            self.compactors[h].extend_from_slice(values);
+        }
+        self.max_size = (0..self.compactors.len()).map(|h| self.capacity(h)).sum();
+        self.size = self.compactors.iter().map(|c| c.len()).sum();
+        while self.size >= self.max_size {
+            self.compress();
+        }
+        self.stats.merge(&other.stats);
+    }
+
+    /// The number of values added.
+    pub fn count(&self) -> usize {
+        self.stats.count()
+    }
+
+    // The values kept by the sketch, sorted, with the number of values they account for.
+    fn weighted_values(&self) -> Vec<(T, u64)> {
+        let mut weighted_values = self.compactors
+            .iter()
+            .enumerate()
+            .flat_map(|(h, values)| values.iter().map(move |v| (*v, 1u64 << h)))
+            .collect::<Vec<(T, u64)>>();
+        weighted_values.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        weighted_values
+    }
+
+    /// Returns the approximate number of values lower than or equal to `value`.
+    pub fn rank(&self, value: T) -> usize {
+        self.compactors
+            .iter()
+            .enumerate()
+            .map(|(h, values)| values.iter().filter(|v| **v <= value).count() << h)
+            .sum()
+    }
+
+    /// Returns the approximate `p` quantile (`p` being between 0 and 1) of the values,
+    /// ie. the value of rank `floor(p * n + 0.49)` (the rule of [`get_quantiles`]).
+    ///
+    /// [`get_quantiles`]: fn.get_quantiles.html
+    pub fn quantile(&self, p: f64) -> ClassifResult<T> {
+        if !(0. ..=1.).contains(&p) {
+            return Err(ClassifError::InvalidParameter("quantile probability has to be between 0 and 1"));
+        }
+        Ok(self.quantiles(&[p])?[0])
+    }
+
+    // Approximate quantiles for the sorted probabilities `probs`.
+    fn quantiles(&self, probs: &[f64]) -> ClassifResult<Vec<T>> {
+        let nb_elem = self.count();
+        if nb_elem < 1 {
+            return Err(ClassifError::TooFewValues(nb_elem, 1));
+        }
+        let weighted_values = self.weighted_values();
+        let mut result = Vec::with_capacity(probs.len());
+        let mut cumulative = 0;
+        let mut ix = 0;
+        for &p in probs {
+            let target = ((p * nb_elem as f64 + 0.49).floor() as u64).max(1);
+            while ix < weighted_values.len() && cumulative + weighted_values[ix].1 < target {
+                cumulative += weighted_values[ix].1;
+                ix += 1;
+            }
+            let value = weighted_values
+                .get(ix)
+                .map(|v| v.0)
+                .unwrap_or_else(|| self.stats.max().unwrap());
+            // The extreme values are known exactly:
+            result.push(value.max(self.stats.min()?).min(self.stats.max()?));
+        }
+        Ok(result)
+    }
+
+    /// Compute the approximate quantiles breaks of `nb_class` classes on the values
+    /// (the first and last breaks being the exact minimum and maximum).
+    pub fn quantile_breaks(&self, nb_class: u32) -> ClassifResult<Vec<T>> {
+        let nb_elem = self.count();
+        if nb_elem < 2 {
+            return Err(ClassifError::TooFewValues(nb_elem, 2));
+        }
+        if nb_class < 2 || nb_class as usize > nb_elem {
+            return Err(ClassifError::InvalidClassNumber(nb_class));
+        }
+        if self.stats.min()? == self.stats.max()? {
+            return Err(ClassifError::DegenerateSerie);
+        }
+        let probs = (1..nb_class)
+            .map(|i| i as f64 / nb_class as f64)
+            .collect::<Vec<f64>>();
+        let mut breaks = Vec::with_capacity(nb_class as usize + 1);
+        breaks.push(self.stats.min()?);
+        breaks.extend(self.quantiles(&probs)?);
+        breaks.push(self.stats.max()?);
+        Ok(breaks)
+    }
+}
+
+impl<T> BoundsInfo<T>
+    where T: Float + NumAssignOps
+{
+    /// Compute the approximate bounds of `nb_class` classes of the `Quantiles` method
+    /// on the values summarized by `sketch` (see [`QuantileSketch`] for the error bound).
+    ///
+    /// [`QuantileSketch`]: struct.QuantileSketch.html
+    pub fn from_sketch(sketch: &QuantileSketch<T>, nb_class: u32) -> ClassifResult<Self> {
+        let breaks = sketch.quantile_breaks(nb_class)?;
+        Ok(BoundsInfo {
+               type_classif: Classification::Quantiles,
+               nb_class,
+               bounds: breaks,
+               min: sketch.stats.min()?,
+               max: sketch.stats.max()?,
+               mean: sketch.stats.mean()?,
+               ht_index: None,
+               centers: None,
+               missing_values: MissingValues::Error,
+               nb_missing: 0,
+               closure: IntervalClosure::default(),
+           })
+    }
+}
@@ -1,94 +1,279 @@
 use num_traits::{Float, NumAssignOps};
-use error::{ClassifError, ClassifResult, MayFail};
+use error::{ClassifError, ClassifResult, MayFail, check_values, check_weights};
 
 /// Compute the mean of a list of values.
-pub fn mean<T>(values: &[T]) -> T
+pub fn mean<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
+    check_values(values, 1, None)?;
     let mut sum: T = T::zero();
-    values.iter().map(|v| sum += *v).collect::<Vec<_>>();
-    sum / T::from(values.len()).unwrap()
+    values.iter().for_each(|v| sum += *v);
+    Ok(sum / T::from(values.len()).unwrap())
+}
+
+/// Compute the weighted mean of a list of values,
+/// `weights` being the (positive) weight of each value.
+pub fn weighted_mean<T>(values: &[T], weights: &[T]) -> ClassifResult<T>
+    where T: Float + NumAssignOps
+{
+    check_values(values, 1, None)?;
+    check_weights(values, weights)?;
+    let mut sum = T::zero();
+    let mut sum_weights = T::zero();
+    for (v, w) in values.iter().zip(weights) {
+        sum += *v * *w;
+        sum_weights += *w;
+    }
+    Ok(sum / sum_weights)
 }
 
 /// Compute the median value, ie. the middle number of a list a value,
 /// ie. the value corresponding to the 0.5 quantile.
-pub fn median<T>(values: &[T]) -> T
+pub fn median<T>(values: &[T]) -> ClassifResult<T>
     where T: Float
 {
+    check_values(values, 1, None)?;
     let mut v = values.to_vec();
     v.sort_by(|a, b| a.partial_cmp(b).unwrap());
     let n = values.len();
     let m = (n as f64 / 2.).ceil() as usize;
-    if n % 2 != 0 {
-        v[m - 1usize]
+    if n % 2 == 1 {
+        Ok(v[m - 1usize])
+    } else {
+        Ok((v[m - 1usize] + v[m]) / T::from(2.0).unwrap())
+    }
+}
+
+/// The rule used to compute a quantile, ie. the sample quantile types 1 to 9
+/// defined by Hyndman and Fan (1996), as available in R (`quantile(x, type = ...)`)
+/// and NumPy (`np.quantile(x, method = ...)`).
+///
+/// The default is the `Legacy` rule, used by [`get_quantiles`].
+///
+/// [`get_quantiles`]: ../fn.get_quantiles.html
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QuantileMethod {
+    /// The rule historically used by [`get_quantiles`]: the `p` quantile is the value
+    /// of rank `floor(p * n + 0.49)`.
+    ///
+    /// [`get_quantiles`]: ../fn.get_quantiles.html
+    #[default]
+    Legacy,
+    /// Inverse of the empirical distribution function (NumPy's "inverted_cdf").
+    Type1,
+    /// Same as `Type1` but averaging at discontinuities (NumPy's "averaged_inverted_cdf").
+    Type2,
+    /// Nearest even order statistic (SAS definition, NumPy's "closest_observation").
+    Type3,
+    /// Linear interpolation of the empirical distribution function (NumPy's "interpolated_inverted_cdf").
+    Type4,
+    /// Piecewise linear function where the knots are the midpoints of the steps of the
+    /// empirical distribution function (NumPy's "hazen").
+    Type5,
+    /// `p[k] = k / (n + 1)`, used by Minitab and SPSS (NumPy's "weibull").
+    Type6,
+    /// `p[k] = (k - 1) / (n - 1)`, the default of R and NumPy (NumPy's "linear").
+    Type7,
+    /// Approximately median-unbiased whatever the distribution (NumPy's "median_unbiased").
+    Type8,
+    /// Approximately unbiased for normally distributed values (NumPy's "normal_unbiased").
+    Type9,
+}
+
+// Value of the `p` quantile of (non empty) sorted values.
+pub(crate) fn sorted_quantile<T>(sorted_values: &[T], p: f64, method: QuantileMethod) -> T
+    where T: Float
+{
+    let n = sorted_values.len();
+    // Order statistic of (1-based) rank `j`, clamped to the range of the values:
+    let x = |j: i64| sorted_values[(j.max(1) as usize).min(n) - 1];
+    let np = n as f64 * p;
+    let m = match method {
+        QuantileMethod::Legacy => {
+            return x((np + 0.49).floor() as i64);
+        }
+        QuantileMethod::Type1 | QuantileMethod::Type2 | QuantileMethod::Type4 => 0.,
+        QuantileMethod::Type3 => -0.5,
+        QuantileMethod::Type5 => 0.5,
+        QuantileMethod::Type6 => p,
+        QuantileMethod::Type7 => 1. - p,
+        QuantileMethod::Type8 => (p + 1.) / 3.,
+        QuantileMethod::Type9 => p / 4. + 3. / 8.,
+    };
+    // Same tolerance as R, to avoid floating point artifacts on discontinuous types:
+    let fuzz = 4. * f64::EPSILON;
+    let j = (np + m + fuzz).floor();
+    let mut g = np + m - j;
+    if g.abs() < fuzz {
+        g = 0.;
+    }
+    let j = j as i64;
+    let gamma = match method {
+        QuantileMethod::Type1 => if g > 0. { 1. } else { 0. },
+        QuantileMethod::Type2 => if g > 0. { 1. } else { 0.5 },
+        QuantileMethod::Type3 => if g > 0. || j % 2 == 1 { 1. } else { 0. },
+        _ => g,
+    };
+    if gamma == 0. {
+        x(j)
+    } else if gamma == 1. {
+        x(j + 1)
     } else {
-        (v[m - 1usize] + v[m]) / T::from(2.0).unwrap()
+        let gamma = T::from(gamma).unwrap();
+        (T::one() - gamma) * x(j) + gamma * x(j + 1)
+    }
+}
+
+/// Compute the `p` quantile (`p` being between 0 and 1) of a list of values
+/// according to the given `method`.
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::stats::{quantile, QuantileMethod};
+/// # fn main() {
+/// let values = [3., 1., 2., 4., 5., 6., 7., 8., 9., 10.];
+/// assert_eq!(quantile(&values, 0.25, QuantileMethod::Type7).unwrap(), 3.25);
+/// assert_eq!(quantile(&values, 0.25, QuantileMethod::Type6).unwrap(), 2.75);
+/// # }
+/// ```
+pub fn quantile<T>(values: &[T], p: f64, method: QuantileMethod) -> ClassifResult<T>
+    where T: Float
+{
+    check_values(values, 1, None)?;
+    if !(0. ..=1.).contains(&p) {
+        return Err(ClassifError::InvalidParameter("quantile probability has to be between 0 and 1"));
     }
+    let mut v = values.to_vec();
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(sorted_quantile(&v, p, method))
+}
+
+/// Compute the `p` percentile (`p` being between 0 and 100) of a list of values
+/// according to the given `method`.
+pub fn percentile<T>(values: &[T], p: f64, method: QuantileMethod) -> ClassifResult<T>
+    where T: Float
+{
+    if !(0. ..=100.).contains(&p) {
+        return Err(ClassifError::InvalidParameter("percentile has to be between 0 and 100"));
+    }
+    quantile(values, p / 100., method)
 }
 
 /// Compute the kurtosis value of list of values.
 /// The implementation is based on Fischer's definition (normal ==> 0.0)
-/// and use unbiased estimators.
-pub fn kurtosis<T>(values: &[T]) -> T
+/// and use unbiased estimators (so at least 4 values are required).
+pub fn kurtosis<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
     let nb_elem = values.len();
-    let mean = mean(&values);
+    if nb_elem < 4 {
+        return Err(ClassifError::TooFewValues(nb_elem, 4));
+    }
+    let mean = mean(values)?;
     let mut temp_value;
     let mut second_central_moment = T::zero();
     let mut fourth_central_moment = T::zero();
-    for i in 0..nb_elem {
-        temp_value = values[i] - mean;
+    for v in values {
+        temp_value = *v - mean;
         second_central_moment += temp_value * temp_value;
         fourth_central_moment += temp_value * temp_value * temp_value * temp_value;
     }
+    if second_central_moment == T::zero() {
+        return Err(ClassifError::DegenerateSerie);
+    }
     let n = T::from(nb_elem).unwrap();
-    (n - T::from(1).unwrap()) / ((n - T::from(2).unwrap()) * (n - T::from(3).unwrap())) *
+    Ok((n - T::from(1).unwrap()) / ((n - T::from(2).unwrap()) * (n - T::from(3).unwrap())) *
     (n * (n + T::from(1).unwrap()) * fourth_central_moment /
      (second_central_moment * second_central_moment) -
-     T::from(3).unwrap() * (n - T::from(1).unwrap()))
+     T::from(3).unwrap() * (n - T::from(1).unwrap())))
+}
+
+/// Compute the skewness value of a list of values, using the adjusted
+/// Fisher-Pearson standardized moment coefficient (so at least 3 values are required).
+pub fn skewness<T>(values: &[T]) -> ClassifResult<T>
+    where T: Float + NumAssignOps
+{
+    let nb_elem = values.len();
+    if nb_elem < 3 {
+        return Err(ClassifError::TooFewValues(nb_elem, 3));
+    }
+    let mean = mean(values)?;
+    let mut second_central_moment = T::zero();
+    let mut third_central_moment = T::zero();
+    for v in values {
+        let temp_value = *v - mean;
+        second_central_moment += temp_value * temp_value;
+        third_central_moment += temp_value * temp_value * temp_value;
+    }
+    if second_central_moment == T::zero() {
+        return Err(ClassifError::DegenerateSerie);
+    }
+    Ok(adjusted_skewness(T::from(nb_elem).unwrap(), second_central_moment, third_central_moment))
+}
+
+// Skewness computed from the number of values and the sums of
+// their squared and cubed deviations from the mean.
+fn adjusted_skewness<T: Float>(n: T, m2: T, m3: T) -> T {
+    let two = T::from(2).unwrap();
+    n * (n - T::one()).sqrt() * m3 / ((n - two) * m2.powf(T::from(1.5).unwrap()))
 }
 
 /// Compute the sum of deviations to the Nth power.
 /// (i.e. sum of squared deviations when n=2, sum of cubed deviations when n=3, etc.)
-pub fn sum_pow_deviations<T>(values: &[T], n: i32) -> T
+pub fn sum_pow_deviations<T>(values: &[T], n: i32) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
-    let mean = mean(&values);
-    let nb_elem = values.len();
+    let mean = mean(values)?;
     let mut sum = T::zero();
-    for i in 0..nb_elem {
-        sum += (values[i] - mean).powi(n);
+    for v in values {
+        sum += (*v - mean).powi(n);
     }
-    sum
+    Ok(sum)
 }
 
 /// Compute the variance of a list of values.
 /// The variance is the sum of squared deviations from the mean.
-pub fn variance<T>(values: &[T]) -> T
+pub fn variance<T>(values: &[T]) -> ClassifResult<T>
+    where T: Float + NumAssignOps
+{
+    Ok(sum_pow_deviations(values, 2)? / T::from(values.len()).unwrap())
+}
+
+/// Compute the weighted variance of a list of values,
+/// `weights` being the (positive) weight of each value.
+pub fn weighted_variance<T>(values: &[T], weights: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
-    sum_pow_deviations(values, 2) / T::from(values.len()).unwrap()
+    let mean = weighted_mean(values, weights)?;
+    let mut sum = T::zero();
+    let mut sum_weights = T::zero();
+    for (v, w) in values.iter().zip(weights) {
+        sum += *w * (*v - mean).powi(2);
+        sum_weights += *w;
+    }
+    Ok(sum / sum_weights)
 }
 
 /// Compute the standard deviation of a list of values.
-pub fn standard_deviation<T>(values: &[T]) -> T
+pub fn standard_deviation<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
-    T::sqrt(variance(&values))
+    Ok(T::sqrt(variance(values)?))
 }
 
 /// Compute the root mean square of list of values.
-pub fn rootmeansquare<T>(values: &[T]) -> T
+pub fn rootmeansquare<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
+    check_values(values, 1, None)?;
     let sum: T = values
         .iter()
         .fold(T::zero(), |mut s, v| {
             s += v.powi(2);
             s
         });
-    (sum / T::from(values.len()).unwrap()).sqrt()
+    Ok((sum / T::from(values.len()).unwrap()).sqrt())
 }
 
 /// This mean is calculated by taking the reciprocal of the arithmetic mean
@@ -113,6 +298,7 @@ pub fn rootmeansquare<T>(values: &[T]) -> T
 pub fn harmonic_mean<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
+    check_values(values, 1, None)?;
     let mut reciprocal_sum = T::zero();
     for v in values {
         if *v <= T::zero() {
@@ -145,6 +331,7 @@ pub fn harmonic_mean<T>(values: &[T]) -> ClassifResult<T>
 pub fn geometric_mean<T>(values: &[T]) -> ClassifResult<T>
     where T: Float + NumAssignOps
 {
+    check_values(values, 1, None)?;
     let mut val = T::one();
     for v in values {
         if *v <= T::zero() {
@@ -154,3 +341,211 @@ pub fn geometric_mean<T>(values: &[T]) -> ClassifResult<T>
     }
     Ok(val.powf(T::one() / T::from(values.len()).unwrap()))
 }
+
+/// Accumulator computing statistics on values received one by one (or by chunks),
+/// without keeping them in memory and in a single pass, using the
+/// updating formulas of Welford and Terriberry.
+///
+/// Accumulators fed with distinct parts of the values (for example by parallel workers)
+/// can be combined with [`merge`]. The statistics are the same as those computed
+/// by the functions of this module (up to floating point rounding).
+///
+/// ```rust
+/// # extern crate classif;
+/// # use classif::stats::OnlineStats;
+/// # fn main() {
+/// let mut stats = OnlineStats::new();
+/// stats.extend(&[2., 4., 4., 4.]).unwrap();
+/// let mut other = OnlineStats::new();
+/// other.push(5.).unwrap();
+/// other.extend(&[5., 7., 9.]).unwrap();
+/// stats.merge(&other);
+/// assert_eq!(stats.count(), 8);
+/// assert_eq!(stats.mean().unwrap(), 5.);
+/// assert_eq!(stats.standard_deviation().unwrap(), 2.);
+/// assert_eq!(stats.max().unwrap(), 9.);
+/// # }
+/// ```
+/// [`merge`]: struct.OnlineStats.html#method.merge
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OnlineStats<T> {
+    count: usize,
+    mean: T,
+    // Sums of the deviations from the mean to the 2nd, 3rd and 4th powers:
+    m2: T,
+    m3: T,
+    m4: T,
+    sum_squares: T,
+    min: T,
+    max: T,
+}
+
+impl<T> Default for OnlineStats<T>
+    where T: Float + NumAssignOps
+{
+    fn default() -> Self {
+        OnlineStats::new()
+    }
+}
+
+impl<T> OnlineStats<T>
+    where T: Float + NumAssignOps
+{
+    /// Create an accumulator without any value.
+    pub fn new() -> Self {
+        OnlineStats {
+            count: 0,
+            mean: T::zero(),
+            m2: T::zero(),
+            m3: T::zero(),
+            m4: T::zero(),
+            sum_squares: T::zero(),
+            min: T::infinity(),
+            max: T::neg_infinity(),
+        }
+    }
+
+    /// Add a value, failing (without adding it) if it is NaN or infinite.
+    pub fn push(&mut self, value: T) -> ClassifResult<()> {
+        if !value.is_finite() {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        let n1 = T::from(self.count).unwrap();
+        self.count += 1;
+        let n = T::from(self.count).unwrap();
+        let delta = value - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * n1;
+        let (two, three, four, six) = (T::from(2).unwrap(),
+                                       T::from(3).unwrap(),
+                                       T::from(4).unwrap(),
+                                       T::from(6).unwrap());
+        self.mean += delta_n;
+        self.m4 += term * delta_n2 * (n * n - three * n + three) + six * delta_n2 * self.m2 -
+                   four * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - two) - three * delta_n * self.m2;
+        self.m2 += term;
+        self.sum_squares += value * value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        Ok(())
+    }
+
+    /// Add a chunk of values, failing (without adding any of them)
+    /// if they contain NaN or infinite values.
+    pub fn extend(&mut self, values: &[T]) -> ClassifResult<()> {
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(ClassifError::NonFiniteValue);
+        }
+        for &v in values {
+            self.push(v)?;
+        }
+        Ok(())
+    }
+
+    /// Combine the values of `other` into this accumulator.
+    pub fn merge(&mut self, other: &OnlineStats<T>) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let (na, nb) = (T::from(self.count).unwrap(), T::from(other.count).unwrap());
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let (three, four, six) = (T::from(3).unwrap(), T::from(4).unwrap(), T::from(6).unwrap());
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+        let m3 = self.m3 + other.m3 + delta2 * delta * na * nb * (na - nb) / (n * n) +
+                 three * delta * (na * other.m2 - nb * self.m2) / n;
+        let m4 = self.m4 + other.m4 +
+                 delta2 * delta2 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n) +
+                 six * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n) +
+                 four * delta * (na * other.m3 - nb * self.m3) / n;
+        self.count += other.count;
+        self.mean += delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+        self.sum_squares += other.sum_squares;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    // Fail if less than `min_len` values were added.
+    fn check_count(&self, min_len: usize) -> ClassifResult<()> {
+        if self.count < min_len {
+            return Err(ClassifError::TooFewValues(self.count, min_len));
+        }
+        Ok(())
+    }
+
+    /// The number of values added.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean of the values added.
+    pub fn mean(&self) -> ClassifResult<T> {
+        self.check_count(1)?;
+        Ok(self.mean)
+    }
+
+    /// The variance of the values added.
+    pub fn variance(&self) -> ClassifResult<T> {
+        self.check_count(1)?;
+        Ok(self.m2 / T::from(self.count).unwrap())
+    }
+
+    /// The standard deviation of the values added.
+    pub fn standard_deviation(&self) -> ClassifResult<T> {
+        Ok(self.variance()?.sqrt())
+    }
+
+    /// The skewness of the values added (see [`skewness`]).
+    ///
+    /// [`skewness`]: fn.skewness.html
+    pub fn skewness(&self) -> ClassifResult<T> {
+        self.check_count(3)?;
+        if self.m2 == T::zero() {
+            return Err(ClassifError::DegenerateSerie);
+        }
+        Ok(adjusted_skewness(T::from(self.count).unwrap(), self.m2, self.m3))
+    }
+
+    /// The kurtosis of the values added (see [`kurtosis`]).
+    ///
+    /// [`kurtosis`]: fn.kurtosis.html
+    pub fn kurtosis(&self) -> ClassifResult<T> {
+        self.check_count(4)?;
+        if self.m2 == T::zero() {
+            return Err(ClassifError::DegenerateSerie);
+        }
+        let n = T::from(self.count).unwrap();
+        let (one, two, three) = (T::one(), T::from(2).unwrap(), T::from(3).unwrap());
+        Ok((n - one) / ((n - two) * (n - three)) *
+           (n * (n + one) * self.m4 / (self.m2 * self.m2) - three * (n - one)))
+    }
+
+    /// The minimum of the values added.
+    pub fn min(&self) -> ClassifResult<T> {
+        self.check_count(1)?;
+        Ok(self.min)
+    }
+
+    /// The maximum of the values added.
+    pub fn max(&self) -> ClassifResult<T> {
+        self.check_count(1)?;
+        Ok(self.max)
+    }
+
+    /// The root mean square of the values added.
+    pub fn rootmeansquare(&self) -> ClassifResult<T> {
+        self.check_count(1)?;
+        Ok((self.sum_squares / T::from(self.count).unwrap()).sqrt())
+    }
+}